@@ -0,0 +1,87 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::engine::{Engine, TxStatus};
+use crate::state::Position;
+
+/// USDC mint (mainnet)
+pub const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+/// Wrapped SOL mint
+pub const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// One marked-to-market valuation of the trading wallet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EquitySnapshot {
+    pub ts: String, // RFC3339 UTC
+    pub day_key: String,
+    pub usdc: f64,
+    pub sol: f64,
+    pub sol_price_usdc: f64,
+    /// Open positions marked at the Jupiter exit quote (a position whose sell
+    /// already landed counts as the USDC it received).
+    pub positions_usdc: f64,
+    pub equity_usdc: f64,
+}
+
+/// Values the wallet: USDC + SOL + open positions, all in USDC.
+#[derive(Clone)]
+pub struct BalanceOracle {
+    engine: Engine,
+}
+
+impl BalanceOracle {
+    pub fn new(engine: Engine) -> Self {
+        Self { engine }
+    }
+
+    pub async fn snapshot(&self, positions: &[Position], day_key: &str) -> Result<EquitySnapshot> {
+        let owner = self.engine.wallet_pubkey()?;
+
+        let usdc = self.engine.token_balance_ui(&owner, USDC_MINT).await?;
+        let sol = self.engine.sol_balance(&owner).await?;
+        let sol_price_usdc = self.engine.price_quote_per_base(WSOL_MINT, USDC_MINT).await?;
+
+        let mut positions_usdc = 0.0;
+        for p in positions {
+            if let Some(pending) = &p.pending_sell {
+                // Once the sell landed its USDC is in the wallet; don't count the tokens too.
+                let status = self
+                    .engine
+                    .tx_status(&pending.signature, pending.last_valid_block_height)
+                    .await?;
+                if status == TxStatus::Landed {
+                    continue;
+                }
+            }
+            match self
+                .engine
+                .quote_out_ui(&p.base_mint, &p.quote_mint, p.base_amount)
+                .await
+            {
+                Ok(v) => positions_usdc += v,
+                Err(e) => {
+                    // A position we cannot price counts at entry size rather than zero,
+                    // so a flaky quote does not look like a total loss.
+                    warn!(error = %e, mint = %p.base_mint, "balance.position_mark_failed");
+                    positions_usdc += p.size_usdc;
+                }
+            }
+        }
+
+        Ok(EquitySnapshot {
+            ts: chrono::Utc::now().to_rfc3339(),
+            day_key: day_key.to_string(),
+            usdc,
+            sol,
+            sol_price_usdc,
+            positions_usdc,
+            equity_usdc: usdc + sol * sol_price_usdc + positions_usdc,
+        })
+    }
+}
+
+/// Appends a snapshot as one JSON line.
+pub fn append_snapshot(path: &str, snap: &EquitySnapshot) -> Result<()> {
+    crate::logger::append_line(path, &serde_json::to_string(snap)?)
+}
@@ -0,0 +1,359 @@
+pub mod slack;
+pub mod telegram;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+use rand::{thread_rng, Rng};
+use tracing::{info, warn};
+
+use crate::config::Config;
+use crate::control::Control;
+use crate::risk::BotMode;
+
+/// Where a command came from. Operator allowlists are per platform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Platform {
+    Slack,
+    Telegram,
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Slack => "slack",
+            Self::Telegram => "telegram",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChatCommand {
+    Help,
+    Status,
+    Positions,
+    Close { id: String },
+    Pause { reason: Option<String> },
+    Resume { reason: Option<String> },
+    Confirm { code: String },
+    Cancel,
+}
+
+/// Parses `/close abc`, `close abc` or Telegram's `/close@my_bot abc`.
+pub fn parse_command(text: &str) -> Result<ChatCommand, String> {
+    let text = text.trim();
+    let (head, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+    let name = head.trim_start_matches('/');
+    let name = name.split_once('@').map_or(name, |(n, _)| n).to_lowercase();
+    let rest = rest.trim();
+    let reason = (!rest.is_empty()).then(|| rest.to_string());
+
+    match name.as_str() {
+        "" | "help" | "start" => Ok(ChatCommand::Help),
+        "status" => Ok(ChatCommand::Status),
+        "positions" => Ok(ChatCommand::Positions),
+        "close" if !rest.is_empty() => Ok(ChatCommand::Close {
+            id: rest.split_whitespace().next().unwrap_or_default().to_string(),
+        }),
+        "close" => Err("usage: /close <position id>".into()),
+        "pause" => Ok(ChatCommand::Pause { reason }),
+        "resume" => Ok(ChatCommand::Resume { reason }),
+        "confirm" if !rest.is_empty() => Ok(ChatCommand::Confirm { code: rest.to_string() }),
+        "confirm" => Err("usage: /confirm <code>".into()),
+        "cancel" => Ok(ChatCommand::Cancel),
+        other => Err(format!("unknown command /{other}; try /help")),
+    }
+}
+
+/// Parses `id:name,id:name` (chat user id -> operator name).
+pub fn parse_operator_ids(raw: &str) -> Result<HashMap<String, String>> {
+    let mut out = HashMap::new();
+    for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (id, name) = entry
+            .split_once(':')
+            .ok_or_else(|| anyhow!("operator entry '{entry}' must be user_id:name"))?;
+        if id.trim().is_empty() || name.trim().is_empty() {
+            return Err(anyhow!("operator entry '{entry}' needs both a user id and a name"));
+        }
+        out.insert(id.trim().to_string(), name.trim().to_string());
+    }
+    Ok(out)
+}
+
+/// An action waiting for `/confirm <code>` from the operator who asked for it.
+#[derive(Debug, Clone)]
+enum PendingAction {
+    Close { id: String },
+    Resume { reason: String },
+}
+
+#[derive(Debug)]
+struct Pending {
+    code: String,
+    action: PendingAction,
+    expires: Instant,
+    /// Wrong codes entered so far.
+    failed: u32,
+}
+
+/// Wrong codes allowed before the pending action is dropped: the code is only
+/// 4 digits, so it must not be guessable by retrying.
+const MAX_CONFIRM_ATTEMPTS: u32 = 3;
+
+type PendingMap = HashMap<(Platform, String), Pending>;
+
+/// Takes the action pending for `key` if `code` matches. Expired requests and
+/// requests with too many wrong codes are dropped; the error is the reply.
+fn take_confirmed(pending: &mut PendingMap, key: &(Platform, String), code: &str) -> Result<PendingAction, String> {
+    let Some(p) = pending.get_mut(key) else {
+        return Err("Nothing to confirm.".into());
+    };
+    if p.expires < Instant::now() {
+        pending.remove(key);
+        return Err("That confirmation expired; run the command again.".into());
+    }
+    if p.code == code.trim() {
+        return Ok(pending.remove(key).expect("pending entry").action);
+    }
+    p.failed += 1;
+    if p.failed >= MAX_CONFIRM_ATTEMPTS {
+        pending.remove(key);
+        warn!(platform = %key.0, user_id = %key.1, "chatops.confirm.dropped");
+        return Err("Wrong code. Too many attempts; request dropped, run the command again.".into());
+    }
+    Err(format!("Wrong code ({} attempts left).", MAX_CONFIRM_ATTEMPTS - p.failed))
+}
+
+const HELP: &str = "Commands:
+/status - mode, daily PnL, equity
+/positions - open positions
+/close <id> - market-close a position (asks for confirmation)
+/pause [reason] - switch to READ_ONLY (no new entries)
+/resume [reason] - back to TRADING (asks for confirmation)
+/confirm <code>, /cancel";
+
+/// Operator commands from chat, run against the same state and engine as the
+/// daemon (through [`Control`]).
+///
+/// Only allowlisted user ids may run commands. Pausing takes effect at once
+/// since it only reduces risk; closing a position and resuming trading must
+/// be confirmed with a one-time code by the same operator.
+pub struct ChatOps {
+    control: Control,
+    operators: HashMap<Platform, HashMap<String, String>>,
+    confirm_ttl: Duration,
+    pending: Mutex<PendingMap>,
+}
+
+impl ChatOps {
+    pub fn from_config(cfg: &Config, control: Control) -> Result<Self> {
+        let mut operators = HashMap::new();
+        operators.insert(
+            Platform::Slack,
+            parse_operator_ids(cfg.slack_operators.as_deref().unwrap_or_default())?,
+        );
+        operators.insert(
+            Platform::Telegram,
+            parse_operator_ids(cfg.telegram_operators.as_deref().unwrap_or_default())?,
+        );
+        Ok(Self {
+            control,
+            operators,
+            confirm_ttl: Duration::from_secs(cfg.chatops_confirm_secs),
+            pending: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Runs one command for `user_id` and returns the reply text.
+    pub async fn handle(&self, platform: Platform, user_id: &str, text: &str) -> String {
+        let Some(name) = self.operators.get(&platform).and_then(|ops| ops.get(user_id)) else {
+            warn!(%platform, user_id, "chatops.unauthorized");
+            return format!("Not authorized (user id {user_id}).");
+        };
+        let operator = format!("{name}@{platform}");
+
+        let cmd = match parse_command(text) {
+            Ok(c) => c,
+            Err(msg) => return msg,
+        };
+        info!(%operator, command = ?cmd, "chatops.command");
+
+        match self.run(platform, user_id, &operator, cmd).await {
+            Ok(reply) => reply,
+            Err(e) => format!("Error: {e}"),
+        }
+    }
+
+    async fn run(&self, platform: Platform, user_id: &str, operator: &str, cmd: ChatCommand) -> Result<String> {
+        let key = (platform, user_id.to_string());
+        match cmd {
+            ChatCommand::Help => Ok(HELP.to_string()),
+            ChatCommand::Status => self.status().await,
+            ChatCommand::Positions => self.positions().await,
+
+            ChatCommand::Close { id } => {
+                let positions = self.control.positions().await.map_err(|e| anyhow!(e.1))?;
+                let p = positions
+                    .iter()
+                    .find(|p| p.id == id)
+                    .ok_or_else(|| anyhow!("no open position {id}"))?;
+                let prompt = format!(
+                    "Close position {id} ({}, ${:.2} at {:.6}) at market?",
+                    p.base_mint, p.size_usdc, p.entry_price
+                );
+                Ok(self.ask(key, PendingAction::Close { id }, &prompt))
+            }
+
+            ChatCommand::Pause { reason } => {
+                let reason = reason.unwrap_or_else(|| format!("paused from {platform}"));
+                let c = self
+                    .control
+                    .set_mode(BotMode::ReadOnly, operator, &reason)
+                    .await
+                    .map_err(|e| anyhow!(e.1))?;
+                Ok(format!("{:?} -> {:?}. No new entries; open positions keep their exits.", c.from, c.to))
+            }
+
+            ChatCommand::Resume { reason } => {
+                let reason = reason.unwrap_or_else(|| format!("resumed from {platform}"));
+                Ok(self.ask(key, PendingAction::Resume { reason }, "Resume TRADING (new entries allowed)?"))
+            }
+
+            ChatCommand::Confirm { code } => {
+                let confirmed = take_confirmed(&mut self.pending.lock().expect("pending lock"), &key, &code);
+                match confirmed {
+                    Ok(PendingAction::Close { id }) => self.close(&id, operator).await,
+                    Ok(PendingAction::Resume { reason }) => {
+                        let c = self
+                            .control
+                            .set_mode(BotMode::Trading, operator, &reason)
+                            .await
+                            .map_err(|e| anyhow!(e.1))?;
+                        Ok(format!("{:?} -> {:?}.", c.from, c.to))
+                    }
+                    Err(reply) => Ok(reply),
+                }
+            }
+
+            ChatCommand::Cancel => Ok(match self.pending.lock().expect("pending lock").remove(&key) {
+                Some(_) => "Cancelled.".into(),
+                None => "Nothing to cancel.".into(),
+            }),
+        }
+    }
+
+    /// Stores `action` until the operator confirms it; replaces any earlier request.
+    fn ask(&self, key: (Platform, String), action: PendingAction, prompt: &str) -> String {
+        let code = format!("{:04}", thread_rng().gen_range(0..10_000));
+        let reply = format!(
+            "{prompt}\nReply /confirm {code} within {}s, or /cancel.",
+            self.confirm_ttl.as_secs()
+        );
+        self.pending.lock().expect("pending lock").insert(
+            key,
+            Pending {
+                code,
+                action,
+                expires: Instant::now() + self.confirm_ttl,
+                failed: 0,
+            },
+        );
+        reply
+    }
+
+    async fn status(&self) -> Result<String> {
+        let v = self.control.mode().await.map_err(|e| anyhow!(e.1))?;
+        let equity = v.equity_usdc.map_or("-".into(), |e| format!("${e:.2}"));
+        Ok(format!(
+            "mode: {:?}\nday {}: pnl ${:.2} (limit -${:.2})\nopen positions: {}\nequity: {equity}",
+            v.mode, v.day_key, v.daily_pnl_usdc, v.daily_loss_limit_usdc, v.open_positions
+        ))
+    }
+
+    async fn positions(&self) -> Result<String> {
+        let positions = self.control.positions().await.map_err(|e| anyhow!(e.1))?;
+        if positions.is_empty() {
+            return Ok("No open positions.".into());
+        }
+        let engine = self.control.engine();
+        let mut lines = vec![];
+        for p in &positions {
+            let now = match engine.price_quote_per_base(&p.base_mint, &p.quote_mint).await {
+                Ok(px) => format!(
+                    "now {px:.6} ({:+.2}%)",
+                    (px - p.entry_price) / p.entry_price * 100.0
+                ),
+                Err(_) => "now ?".into(),
+            };
+            lines.push(format!(
+                "{} {} ${:.2} entry {:.6} {now}",
+                p.id, p.base_mint, p.size_usdc, p.entry_price
+            ));
+        }
+        Ok(lines.join("\n"))
+    }
+
+    async fn close(&self, id: &str, operator: &str) -> Result<String> {
+        let results = self
+            .control
+            .close(Some(id), operator, "closed from chat")
+            .await
+            .map_err(|e| anyhow!(e.1))?;
+        Ok(results
+            .iter()
+            .map(|r| match (&r.signature, &r.error) {
+                (Some(sig), _) => format!(
+                    "Closed {} ({}): pnl ${:.2}, tx {sig}",
+                    r.id,
+                    r.base_mint,
+                    r.pnl_usdc.unwrap_or_default()
+                ),
+                (None, Some(e)) => format!("Close {} failed: {e}", r.id),
+                (None, None) => format!("Close {}: no result", r.id),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending_resume(code: &str) -> (PendingMap, (Platform, String)) {
+        let key = (Platform::Telegram, "42".to_string());
+        let mut map = PendingMap::new();
+        map.insert(
+            key.clone(),
+            Pending {
+                code: code.into(),
+                action: PendingAction::Resume { reason: "test".into() },
+                expires: Instant::now() + Duration::from_secs(60),
+                failed: 0,
+            },
+        );
+        (map, key)
+    }
+
+    #[test]
+    fn confirm_takes_the_action_with_the_right_code() {
+        let (mut map, key) = pending_resume("1234");
+        assert!(take_confirmed(&mut map, &key, "0000").is_err());
+        assert!(matches!(take_confirmed(&mut map, &key, " 1234 "), Ok(PendingAction::Resume { .. })));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn confirm_drops_the_action_after_too_many_wrong_codes() {
+        let (mut map, key) = pending_resume("1234");
+        for _ in 1..MAX_CONFIRM_ATTEMPTS {
+            assert!(take_confirmed(&mut map, &key, "0000").unwrap_err().contains("attempts left"));
+        }
+        assert!(take_confirmed(&mut map, &key, "0000").unwrap_err().contains("request dropped"));
+        assert!(map.is_empty());
+        assert_eq!(take_confirmed(&mut map, &key, "1234").unwrap_err(), "Nothing to confirm.");
+    }
+}
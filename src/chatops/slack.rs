@@ -0,0 +1,116 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use tracing::{info, warn};
+
+use super::{ChatOps, Platform};
+use crate::secret::Secret;
+
+/// Slack asks for a reply within 3s; slower commands answer via `response_url`.
+const INLINE_REPLY_BUDGET: Duration = Duration::from_millis(2500);
+/// Requests older than this are rejected (replay protection).
+const MAX_REQUEST_AGE_SECS: i64 = 300;
+
+/// Slash command payload (form-encoded), the fields we use.
+#[derive(Debug, Deserialize)]
+struct SlashCommand {
+    command: String,
+    #[serde(default)]
+    text: String,
+    user_id: String,
+    response_url: String,
+}
+
+#[derive(Clone)]
+struct SlackState {
+    ops: Arc<ChatOps>,
+    signing_secret: Secret,
+}
+
+/// Checks `X-Slack-Signature` (HMAC-SHA256 of `v0:{timestamp}:{body}`).
+fn verify(headers: &HeaderMap, body: &[u8], secret: &Secret) -> bool {
+    let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let (Some(ts), Some(sig)) = (header("x-slack-request-timestamp"), header("x-slack-signature")) else {
+        return false;
+    };
+    match ts.parse::<i64>() {
+        Ok(t) if (chrono::Utc::now().timestamp() - t).abs() <= MAX_REQUEST_AGE_SECS => {}
+        _ => return false,
+    }
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.expose().as_bytes()).expect("hmac accepts any key size");
+    mac.update(format!("v0:{ts}:").as_bytes());
+    mac.update(body);
+    let expected: String = mac.finalize().into_bytes().iter().map(|b| format!("{b:02x}")).collect();
+    crate::control::ct_eq(sig.as_bytes(), format!("v0={expected}").as_bytes())
+}
+
+fn reply(text: &str) -> Response {
+    Json(serde_json::json!({ "response_type": "ephemeral", "text": text })).into_response()
+}
+
+async fn slash_command(State(st): State<SlackState>, headers: HeaderMap, body: Bytes) -> Response {
+    if !verify(&headers, &body, &st.signing_secret) {
+        warn!("chatops.slack.bad_signature");
+        return (StatusCode::UNAUTHORIZED, "invalid signature").into_response();
+    }
+    let cmd: SlashCommand = match serde_urlencoded::from_bytes(&body) {
+        Ok(c) => c,
+        Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response(),
+    };
+
+    // A single `/solbot status` command works as well as one command per action.
+    let text = match cmd.command.trim_start_matches('/') {
+        "status" | "positions" | "close" | "pause" | "resume" | "confirm" | "cancel" | "help" => {
+            format!("{} {}", cmd.command, cmd.text)
+        }
+        _ => cmd.text.clone(),
+    };
+
+    let ops = st.ops.clone();
+    let user_id = cmd.user_id.clone();
+    let mut task = tokio::spawn(async move { ops.handle(Platform::Slack, &user_id, &text).await });
+
+    match tokio::time::timeout(INLINE_REPLY_BUDGET, &mut task).await {
+        Ok(Ok(text)) => reply(&text),
+        Ok(Err(e)) => reply(&format!("Error: {e}")),
+        Err(_) => {
+            let response_url = cmd.response_url;
+            tokio::spawn(async move {
+                let text = task.await.unwrap_or_else(|e| format!("Error: {e}"));
+                let res = reqwest::Client::new()
+                    .post(&response_url)
+                    .json(&serde_json::json!({ "response_type": "ephemeral", "text": text }))
+                    .send()
+                    .await;
+                if let Err(e) = res {
+                    warn!(error = %e.without_url(), "chatops.slack.response_url_failed");
+                }
+            });
+            reply("Working on it…")
+        }
+    }
+}
+
+/// Serves the Slack slash-command endpoint (`POST /slack/commands`) until the
+/// listener fails. Put it behind a TLS reverse proxy; Slack only calls https.
+pub async fn serve(addr: &str, ops: Arc<ChatOps>, signing_secret: Secret) -> Result<()> {
+    let app = Router::new()
+        .route("/slack/commands", post(slash_command))
+        .with_state(SlackState { ops, signing_secret });
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!(%addr, "chatops.slack.listening");
+    axum::serve(listener, app).await?;
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::{info, warn};
+
+use super::{ChatOps, Platform};
+use crate::secret::Secret;
+
+/// Long-poll timeout passed to `getUpdates`.
+const POLL_TIMEOUT_SECS: u64 = 25;
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Deserialize)]
+struct Updates {
+    ok: bool,
+    #[serde(default)]
+    result: Vec<Update>,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Update {
+    update_id: i64,
+    message: Option<Message>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    chat: Chat,
+    from: Option<User>,
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Chat {
+    id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    id: i64,
+}
+
+struct Bot {
+    token: Secret,
+    http: Client,
+}
+
+impl Bot {
+    fn url(&self, method: &str) -> String {
+        format!("https://api.telegram.org/bot{}/{method}", self.token.expose())
+    }
+
+    async fn updates(&self, offset: i64) -> Result<Vec<Update>> {
+        let resp: Updates = self
+            .http
+            .get(self.url("getUpdates"))
+            .query(&[
+                ("offset", offset.to_string()),
+                ("timeout", POLL_TIMEOUT_SECS.to_string()),
+                ("allowed_updates", r#"["message"]"#.to_string()),
+            ])
+            .send()
+            .await
+            .map_err(|e| anyhow!("getUpdates failed: {}", e.without_url()))?
+            .json()
+            .await
+            .map_err(|e| anyhow!("getUpdates bad response: {}", e.without_url()))?;
+        if !resp.ok {
+            return Err(anyhow!(
+                "getUpdates: {}",
+                resp.description.unwrap_or_else(|| "not ok".into())
+            ));
+        }
+        Ok(resp.result)
+    }
+
+    async fn send(&self, chat_id: i64, text: &str) -> Result<()> {
+        let resp = self
+            .http
+            .post(self.url("sendMessage"))
+            .json(&serde_json::json!({ "chat_id": chat_id, "text": text }))
+            .send()
+            .await
+            .map_err(|e| anyhow!("sendMessage failed: {}", e.without_url()))?;
+        if !resp.status().is_success() {
+            return Err(anyhow!("sendMessage returned {}", resp.status()));
+        }
+        Ok(())
+    }
+}
+
+/// Polls the bot for `/commands` and answers in the same chat. Runs until
+/// the task is dropped; API errors are logged and retried.
+pub async fn poll(token: Secret, ops: Arc<ChatOps>) -> Result<()> {
+    let bot = Bot {
+        token,
+        http: Client::builder()
+            .timeout(Duration::from_secs(POLL_TIMEOUT_SECS + 10))
+            .build()?,
+    };
+    info!("chatops.telegram.polling");
+
+    let mut offset = 0;
+    loop {
+        let updates = match bot.updates(offset).await {
+            Ok(u) => u,
+            Err(e) => {
+                warn!(error = %e, "chatops.telegram.poll_failed");
+                tokio::time::sleep(RETRY_DELAY).await;
+                continue;
+            }
+        };
+
+        for u in updates {
+            offset = offset.max(u.update_id + 1);
+            let Some(Message {
+                chat,
+                from: Some(from),
+                text: Some(text),
+            }) = u.message
+            else {
+                continue;
+            };
+            if !text.starts_with('/') {
+                continue;
+            }
+
+            let reply = ops.handle(Platform::Telegram, &from.id.to_string(), &text).await;
+            if let Err(e) = bot.send(chat.id, &reply).await {
+                warn!(error = %e, "chatops.telegram.reply_failed");
+            }
+        }
+    }
+}
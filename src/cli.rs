@@ -0,0 +1,594 @@
+use anyhow::{anyhow, Result};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use zeroize::Zeroizing;
+
+use crate::config::Config;
+use crate::control::ControlClient;
+use crate::keystore::KeystoreFile;
+use crate::notifier::Notifier;
+use crate::risk::BotMode;
+use crate::secret::Secret;
+use crate::signer::{LocalSigner, SignerPolicy};
+use crate::state::{PersistedState, StateStore};
+
+#[derive(Debug, Parser)]
+#[command(name = "solbot", about = "Claw SOL Bot (Solana Infinity Engine)")]
+pub struct Cli {
+    /// Print machine-readable JSON instead of text.
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the trading daemon (default).
+    Run,
+    /// Mode, PnL and balances from state.json.
+    Status,
+    /// Open positions from state.json.
+    Positions,
+    /// Closed trades from the trade ledger.
+    History {
+        /// Show only the last N trades.
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        /// Show operator mode changes instead of trades.
+        #[arg(long)]
+        modes: bool,
+    },
+    /// Performance stats per strategy, mint and exit reason from the trade ledger.
+    Report {
+        /// Only exits since then: `7d`, `12h` or `YYYY-MM-DD` (start of day in SIE_TZ).
+        #[arg(long)]
+        since: Option<String>,
+        /// Only this strategy (e.g. momentum-scalping).
+        #[arg(long)]
+        strategy: Option<String>,
+        /// Output format (`--json` implies json).
+        #[arg(long, value_enum, default_value_t = ReportFormat::Md)]
+        format: ReportFormat,
+        /// Write to this file instead of stdout.
+        #[arg(long)]
+        out: Option<String>,
+    },
+    /// Trading journal commands.
+    Journal {
+        #[command(subcommand)]
+        action: JournalAction,
+    },
+    /// Configuration commands.
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Encrypted keystore for the trading keypair.
+    Keystore {
+        #[command(subcommand)]
+        action: KeystoreAction,
+    },
+    /// Stand-in remote signer (SIE_SIGNER=remote).
+    Signer {
+        #[command(subcommand)]
+        action: SignerAction,
+    },
+    /// Inspect or override the bot mode through the daemon control API.
+    Mode {
+        #[command(flatten)]
+        api: ApiArgs,
+        #[command(subcommand)]
+        action: ModeAction,
+    },
+    /// Resume trading (shortcut for `mode resume`).
+    Resume {
+        #[command(flatten)]
+        api: ApiArgs,
+        #[arg(long)]
+        reason: String,
+    },
+    /// Market-close one position by id, or all with --all.
+    Close {
+        #[command(flatten)]
+        api: ApiArgs,
+        /// Position id (see `solbot positions`).
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        id: Option<String>,
+        #[arg(long)]
+        all: bool,
+        #[arg(long)]
+        reason: String,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ReportFormat {
+    Md,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum JournalAction {
+    /// Rewrite the Markdown journal from the trade ledger.
+    Render {
+        /// Write here instead of the configured journal path.
+        #[arg(long)]
+        out: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigAction {
+    /// Load the configuration and report problems.
+    Check,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum KeystoreAction {
+    /// Encrypt a Solana CLI keypair file into a new keystore.
+    Import {
+        /// Plaintext keypair (JSON array of 64 bytes).
+        #[arg(long)]
+        keypair: String,
+        /// Keystore file to create (mode 0600, never overwritten).
+        #[arg(long)]
+        out: String,
+        /// Read the passphrase from this file instead of prompting.
+        #[arg(long, env = "SIE_KEYSTORE_PASSPHRASE_FILE")]
+        passphrase_file: Option<String>,
+    },
+    /// Decrypt a keystore back into a plaintext Solana CLI keypair file.
+    Export {
+        #[arg(long)]
+        keystore: String,
+        /// Plaintext keypair file to create (mode 0600, never overwritten).
+        #[arg(long)]
+        out: String,
+        #[arg(long, env = "SIE_KEYSTORE_PASSPHRASE_FILE")]
+        passphrase_file: Option<String>,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SignerAction {
+    /// Sign swap transactions over HTTP with the configured keystore/keypair,
+    /// enforcing its own program allowlist and max notional.
+    Serve {
+        #[arg(long, default_value = "127.0.0.1:8788")]
+        addr: String,
+        #[arg(long, default_value_t = 50.0)]
+        max_notional_usdc: f64,
+        /// Extra program ids to allow (repeatable).
+        #[arg(long)]
+        allow_program: Vec<String>,
+        /// Bearer token clients must present.
+        #[arg(long, env = "SIE_SIGNER_TOKEN", hide_env_values = true)]
+        token: String,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct ApiArgs {
+    /// Control API base URL.
+    #[arg(long, env = "SIE_CONTROL_URL", default_value = "http://127.0.0.1:8787")]
+    pub url: String,
+    /// Operator token (one of SIE_CONTROL_TOKENS on the daemon).
+    #[arg(long, env = "SIE_CONTROL_TOKEN", hide_env_values = true)]
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ModeAction {
+    /// Show the current mode and recent operator changes.
+    Show,
+    /// Resume trading (READ_ONLY/EMERGENCY_STOP -> TRADING).
+    Resume {
+        #[arg(long)]
+        reason: String,
+    },
+    /// Stop opening new positions; exits keep running.
+    ReadOnly {
+        #[arg(long)]
+        reason: String,
+    },
+    /// Liquidate all positions and stop.
+    EmergencyStop {
+        #[arg(long)]
+        reason: String,
+    },
+}
+
+impl ApiArgs {
+    pub fn client(&self) -> Result<ControlClient> {
+        let token = self
+            .token
+            .clone()
+            .ok_or_else(|| anyhow!("operator token required (--token or SIE_CONTROL_TOKEN)"))?;
+        Ok(ControlClient::new(self.url.clone(), token))
+    }
+}
+
+fn print_json<T: Serialize>(v: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(v)?);
+    Ok(())
+}
+
+fn load_state(cfg: &Config) -> Result<PersistedState> {
+    StateStore::new(&cfg.state_path)
+        .load()?
+        .ok_or_else(|| anyhow!("no state at {} (daemon never ran?)", cfg.state_path))
+}
+
+pub fn status(json: bool) -> Result<()> {
+    let cfg = Config::load()?;
+    let st = load_state(&cfg)?;
+
+    if json {
+        return print_json(&serde_json::json!({
+            "mode": st.risk.mode,
+            "day_key": st.risk.daily.day_key,
+            "daily": st.risk.daily,
+            "daily_pnl_usdc": st.risk.daily_total_pnl_usdc(),
+            "starting_balance_usdc": st.risk.starting_balance_usdc,
+            "current_balance_usdc": st.risk.current_balance_usdc,
+            "equity_usdc": st.risk.equity_usdc,
+            "open_positions": st.positions.len(),
+            "last_mode_change": st.mode_history.last(),
+        }));
+    }
+
+    println!("mode:            {:?}", st.risk.mode);
+    println!("day:             {}", st.risk.daily.day_key);
+    println!(
+        "daily pnl:       ${:.2} (realized ${:.2}, unrealized ${:.2})",
+        st.risk.daily_total_pnl_usdc(),
+        st.risk.daily.realized_pnl_usdc,
+        st.risk.daily.unrealized_pnl_usdc
+    );
+    println!(
+        "balance:         ${:.2} (start ${:.2})",
+        st.risk.current_balance_usdc, st.risk.starting_balance_usdc
+    );
+    match st.risk.equity_usdc {
+        Some(eq) => println!("equity (marked): ${eq:.2}"),
+        None => println!("equity (marked): n/a"),
+    }
+    println!("open positions:  {}", st.positions.len());
+    if let Some(c) = st.mode_history.last() {
+        println!(
+            "last override:   {} {:?} -> {:?} by {}: {}",
+            c.ts, c.from, c.to, c.operator, c.reason
+        );
+    }
+    Ok(())
+}
+
+pub fn positions(json: bool) -> Result<()> {
+    let cfg = Config::load()?;
+    let st = load_state(&cfg)?;
+
+    if json {
+        return print_json(&st.positions);
+    }
+    if st.positions.is_empty() {
+        println!("no open positions");
+        return Ok(());
+    }
+
+    println!(
+        "{:<12} {:<44} {:>9} {:>12} {:>12} {:>6} {:>6} {:>5}",
+        "id", "base_mint", "size", "entry", "peak", "sl%", "tp%", "trail"
+    );
+    for p in &st.positions {
+        println!(
+            "{:<12} {:<44} {:>9.2} {:>12.6} {:>12.6} {:>6.1} {:>6.1} {:>5}",
+            p.id,
+            p.base_mint,
+            p.size_usdc,
+            p.entry_price,
+            p.peak_price,
+            p.stop_loss_pct * 100.0,
+            p.take_profit_pct * 100.0,
+            if p.trailing_armed { "armed" } else { "-" }
+        );
+        if let Some(s) = &p.pending_sell {
+            println!("{:<12} selling since {} ({}): {}", "", s.sent_at, s.reason, s.signature);
+        }
+    }
+    Ok(())
+}
+
+pub fn history(json: bool, limit: usize, modes: bool) -> Result<()> {
+    let cfg = Config::load()?;
+
+    if modes {
+        let st = load_state(&cfg)?;
+        let n = st.mode_history.len();
+        let changes = &st.mode_history[n.saturating_sub(limit)..];
+        if json {
+            return print_json(&changes);
+        }
+        for c in changes {
+            println!("{} {:?} -> {:?} by {}: {}", c.ts, c.from, c.to, c.operator, c.reason);
+        }
+        return Ok(());
+    }
+
+    let events = crate::ledger::read(&cfg.trade_ledger_path)?;
+    let exits: Vec<_> = events.iter().filter_map(|e| e.exit()).collect();
+    let exits = &exits[exits.len().saturating_sub(limit)..];
+    if json {
+        return print_json(&exits);
+    }
+    if exits.is_empty() {
+        println!("no closed trades in {}", cfg.trade_ledger_path);
+        return Ok(());
+    }
+
+    for x in exits {
+        println!(
+            "{}  {:<18} {:<20} {:>8.2} USDC  pnl {:>8.2} ({:>6.2}%)  {}",
+            x.ts,
+            x.strategy,
+            x.reason.label(),
+            x.size_usdc,
+            x.pnl_usdc,
+            x.pnl_pct * 100.0,
+            x.base_mint
+        );
+    }
+    let total: f64 = exits.iter().map(|x| x.pnl_usdc).sum();
+    println!("{} trades, pnl ${total:.2}", exits.len());
+    Ok(())
+}
+
+pub fn report(
+    json: bool,
+    since: Option<String>,
+    strategy: Option<String>,
+    format: ReportFormat,
+    out: Option<String>,
+) -> Result<()> {
+    let cfg = Config::load()?;
+    let since = since
+        .map(|s| crate::report::parse_since(&s, &cfg.tz))
+        .transpose()?;
+    let events = crate::ledger::read(&cfg.trade_ledger_path)?;
+    let report = crate::report::build(&events, since, strategy.as_deref());
+
+    let format = if json { ReportFormat::Json } else { format };
+    let text = match format {
+        ReportFormat::Md => crate::report::to_markdown(&report),
+        ReportFormat::Json => serde_json::to_string_pretty(&report)? + "\n",
+        ReportFormat::Csv => crate::report::to_csv(&report),
+    };
+    match out {
+        Some(path) => std::fs::write(&path, text).map_err(|e| anyhow!("writing {path}: {e}"))?,
+        None => print!("{text}"),
+    }
+    Ok(())
+}
+
+pub fn journal(json: bool, action: JournalAction) -> Result<()> {
+    let cfg = Config::load()?;
+    match action {
+        JournalAction::Render { out } => {
+            let events = crate::ledger::read(&cfg.trade_ledger_path)?;
+            let out = out.unwrap_or_else(|| cfg.trading_journal_path.clone());
+            let fmt = crate::journal::Format::from_config(&cfg)?;
+            crate::journal::rebuild(&out, &events, &fmt)?;
+            if json {
+                return print_json(&serde_json::json!({ "path": out, "events": events.len() }));
+            }
+            println!("rendered {} events from {} into {out}", events.len(), cfg.trade_ledger_path);
+        }
+    }
+    Ok(())
+}
+
+pub fn config_check(json: bool) -> Result<()> {
+    let cfg = Config::load_layers()?;
+    let report = cfg.validate();
+
+    if json {
+        print_json(&serde_json::json!({
+            "ok": !report.has_errors(),
+            "profile": cfg.profile,
+            "dry_run": cfg.dry_run,
+            "tz": cfg.tz,
+            "capital_usdc": cfg.capital_usdc,
+            "position_size_usdc": cfg.position_size_usdc,
+            "max_open_positions": cfg.max_open_positions,
+            "slippage_bps": cfg.slippage_bps,
+            "max_slippage_bps": cfg.max_slippage_bps,
+            "keypair_configured": cfg.has_signing_key(),
+            "keystore_configured": cfg.keystore_path.is_some(),
+            "slack_configured": cfg.slack_webhook_url.is_some(),
+            "alert_sinks": Notifier::from_config(&cfg).sink_names(),
+            "issues": report.issues,
+        }))?;
+    } else {
+        println!(
+            "profile={} dry_run={} tz={}",
+            cfg.profile.as_deref().unwrap_or("-"),
+            cfg.dry_run,
+            cfg.tz
+        );
+        println!(
+            "capital=${:.2} position=${:.2} max_open={}",
+            cfg.capital_usdc, cfg.position_size_usdc, cfg.max_open_positions
+        );
+        println!(
+            "daily_loss={:.2}% sl={:.2}% tp={:.2}% trailing_arm={:.2}% hard_stop={:.2}%",
+            cfg.max_daily_loss_pct * 100.0,
+            cfg.stop_loss_pct * 100.0,
+            cfg.take_profit_pct * 100.0,
+            cfg.trailing_arm_pct * 100.0,
+            cfg.portfolio_hard_stop_pct * 100.0
+        );
+        println!("slippage={}bps (max {}bps)", cfg.slippage_bps, cfg.max_slippage_bps);
+        println!(
+            "keypair={} alerts={}",
+            match (&cfg.keystore_path, &cfg.sol_keypair_path) {
+                (Some(_), _) => "keystore",
+                (None, Some(_)) => "plaintext",
+                (None, None) => "missing",
+            },
+            match Notifier::from_config(&cfg).sink_names() {
+                n if n.is_empty() => "none".to_string(),
+                n => n.join(","),
+            }
+        );
+        println!("{report}");
+    }
+
+    if report.has_errors() {
+        return Err(anyhow!("config check failed"));
+    }
+    Ok(())
+}
+
+/// Passphrase from `--passphrase-file` / `SIE_KEYSTORE_PASSPHRASE`, else a tty prompt.
+fn read_passphrase(file: Option<&str>, confirm: bool) -> Result<Zeroizing<String>> {
+    if let Some(p) = crate::keystore::passphrase_from(file)? {
+        return Ok(p);
+    }
+    let p = Zeroizing::new(rpassword::prompt_password("keystore passphrase: ")?);
+    if confirm {
+        let again = Zeroizing::new(rpassword::prompt_password("repeat passphrase: ")?);
+        if *p != *again {
+            return Err(anyhow!("passphrases do not match"));
+        }
+    }
+    Ok(p)
+}
+
+pub fn keystore(json: bool, action: KeystoreAction) -> Result<()> {
+    let (pubkey, out) = match action {
+        KeystoreAction::Import {
+            keypair,
+            out,
+            passphrase_file,
+        } => {
+            let kp = crate::keystore::read_plain_keypair(&keypair)?;
+            let passphrase = read_passphrase(passphrase_file.as_deref(), true)?;
+            if passphrase.chars().count() < 12 {
+                return Err(anyhow!("passphrase must be at least 12 characters"));
+            }
+            KeystoreFile::encrypt(&kp.keypair()?, passphrase.as_bytes())?.write(&out)?;
+            (kp.pubkey(), out)
+        }
+        KeystoreAction::Export {
+            keystore,
+            out,
+            passphrase_file,
+        } => {
+            let file = KeystoreFile::read(&keystore)?;
+            let passphrase = read_passphrase(passphrase_file.as_deref(), false)?;
+            let kp = file.decrypt(passphrase.as_bytes())?;
+            let bytes = Zeroizing::new(kp.keypair()?.to_bytes().to_vec());
+            let raw = Zeroizing::new(serde_json::to_string(&*bytes)?);
+            crate::keystore::write_private(&out, raw.as_bytes())?;
+            (kp.pubkey(), out)
+        }
+    };
+
+    if json {
+        return print_json(&serde_json::json!({ "pubkey": pubkey.to_string(), "out": out }));
+    }
+    println!("wrote {out} (pubkey {pubkey})");
+    Ok(())
+}
+
+pub async fn signer(action: SignerAction) -> Result<()> {
+    let SignerAction::Serve {
+        addr,
+        max_notional_usdc,
+        allow_program,
+        token,
+    } = action;
+    if token.len() < 16 {
+        return Err(anyhow!("signer token must be at least 16 chars"));
+    }
+
+    let cfg = Config::load_layers()?;
+    let signer = LocalSigner::from_config(&cfg)?
+        .ok_or_else(|| anyhow!("no key to sign with (SIE_KEYSTORE_PATH or SOL_KEYPAIR_PATH)"))?;
+    let policy = SignerPolicy::new(&allow_program, max_notional_usdc)?;
+    crate::signer::serve(&addr, signer, policy, Secret::new(token)).await
+}
+
+pub async fn mode(json: bool, api: ApiArgs, action: ModeAction) -> Result<()> {
+    let client = api.client()?;
+
+    let (to, reason) = match action {
+        ModeAction::Show => {
+            let v = client.mode().await?;
+            if json {
+                return print_json(&v);
+            }
+            println!("mode: {:?}", v.mode);
+            println!(
+                "day {}: pnl ${:.2} (limit -${:.2})",
+                v.day_key, v.daily_pnl_usdc, v.daily_loss_limit_usdc
+            );
+            println!(
+                "open positions: {}, equity {}",
+                v.open_positions,
+                v.equity_usdc.map_or("-".into(), |e| format!("${e:.2}"))
+            );
+            for c in v.recent_changes {
+                println!("{} {:?} -> {:?} by {}: {}", c.ts, c.from, c.to, c.operator, c.reason);
+            }
+            return Ok(());
+        }
+        ModeAction::Resume { reason } => (BotMode::Trading, reason),
+        ModeAction::ReadOnly { reason } => (BotMode::ReadOnly, reason),
+        ModeAction::EmergencyStop { reason } => (BotMode::EmergencyStop, reason),
+    };
+
+    let c = client.set_mode(to, &reason).await?;
+    if json {
+        return print_json(&c);
+    }
+    println!("{:?} -> {:?} (operator={}, at {})", c.from, c.to, c.operator, c.ts);
+    Ok(())
+}
+
+pub async fn close(json: bool, api: ApiArgs, id: Option<String>, reason: String) -> Result<()> {
+    let results = api.client()?.close(id.as_deref(), &reason).await?;
+    // A sell that was sent but not yet confirmed carries both a signature and a note.
+    let failed = results.iter().filter(|r| r.signature.is_none()).count();
+
+    if json {
+        print_json(&results)?;
+    } else {
+        if results.is_empty() {
+            println!("no open positions");
+        }
+        for r in &results {
+            match (&r.signature, &r.error) {
+                (Some(sig), Some(_)) => {
+                    println!("{} ({}): sell sent, awaiting confirmation (tx {sig})", r.id, r.base_mint)
+                }
+                (Some(sig), None) => println!(
+                    "closed {} ({}) pnl=${:.2} tx={sig}",
+                    r.id,
+                    r.base_mint,
+                    r.pnl_usdc.unwrap_or_default()
+                ),
+                (None, err) => {
+                    println!("FAILED {} ({}): {}", r.id, r.base_mint, err.as_deref().unwrap_or("unknown"));
+                }
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(anyhow!("{failed} close(s) failed"));
+    }
+    Ok(())
+}
@@ -1,21 +1,70 @@
-use anyhow::{anyhow, Result};
+use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use tracing::info;
+
+use crate::notifier::Severity;
+use crate::secret::{summarize, Secret};
+use crate::validation::ValidationReport;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    /// Profile applied on top of the config file (`SIE_PROFILE`), if any.
+    pub profile: Option<String>,
+
     // RPC
-    pub helius_http_url: String,
-    pub helius_wss_url: String,
-    pub quicknode_http_url: Option<String>,
-    pub quicknode_wss_url: Option<String>,
+    pub helius_http_url: Secret,
+    pub helius_wss_url: Secret,
+    pub quicknode_http_url: Option<Secret>,
+    pub quicknode_wss_url: Option<Secret>,
 
-    // Alerts
-    pub slack_webhook_url: Option<String>,
+    // Alerts (each sink only gets alerts at or above its minimum severity)
+    pub slack_webhook_url: Option<Secret>,
+    pub slack_min_severity: Severity,
+    pub telegram_bot_token: Option<Secret>,
+    pub telegram_chat_id: Option<String>,
+    pub telegram_min_severity: Severity,
+    pub discord_webhook_url: Option<Secret>,
+    pub discord_min_severity: Severity,
+    /// Generic JSON webhook: `{source, ts, severity, text}`.
+    pub alert_webhook_url: Option<Secret>,
+    pub alert_webhook_min_severity: Severity,
+    /// Repeats of the same alert within this window are folded into one digest.
+    pub alert_suppress_secs: u64,
+    /// Raise the severity of an alert still repeating after this long (0 = never).
+    pub alert_escalate_secs: u64,
+    /// Delivery attempts per sink before an alert is dropped to the fallback log.
+    pub alert_max_attempts: u32,
+    /// Transaction link in trade cards; `{sig}` is replaced by the signature.
+    pub explorer_tx_url: String,
+    /// Also send a weekly roll-up when an ISO week ends (the daily summary is always sent).
+    pub weekly_summary: bool,
 
     // Runtime
     pub dry_run: bool,
     pub tz: String,
 
+    // Operator control API (disabled unless tokens are set)
+    pub control_addr: String,
+    pub control_tokens: Option<Secret>, // name:token,name:token
+
+    // Prometheus metrics and health endpoints (GET /metrics, /healthz, /readyz)
+    pub monitoring_addr: String,
+    /// Unhealthy when the positions loop has not completed a tick for this long.
+    pub health_max_tick_age_secs: u64,
+    /// OTLP/HTTP collector for trace spans; export is off when unset.
+    pub otlp_endpoint: Option<String>,
+
+    // Chat commands: Slack slash commands (enabled by the signing secret) and Telegram polling
+    pub chatops_addr: String,
+    pub slack_signing_secret: Option<Secret>,
+    pub slack_operators: Option<String>, // user_id:name,user_id:name
+    pub telegram_commands: bool,
+    pub telegram_operators: Option<String>, // user_id:name,user_id:name
+    /// How long a `/confirm` code stays valid.
+    pub chatops_confirm_secs: u64,
+
     // Risk (defaults match spec)
     pub capital_usdc: f64,
     pub position_size_usdc: f64,
@@ -25,107 +74,642 @@ pub struct Config {
     pub take_profit_pct: f64,
     pub trailing_arm_pct: f64,
     pub portfolio_hard_stop_pct: f64,
+    /// Close the worst losing position each tick while the daily limit
+    /// (realized + unrealized) is breached, instead of only going READ_ONLY.
+    pub force_reduce_on_daily_loss: bool,
+
+    // Balance oracle (marked-to-market equity)
+    pub equity_interval_secs: u64,
 
     // Execution
     pub jupiter_base_url: String,
+    /// DexScreener-compatible API for the journal's market context; empty disables it.
+    pub market_data_url: String,
     pub slippage_bps: u64,
     pub max_slippage_bps: u64,
 
     // Keys
-    pub sol_keypair_path: Option<String>,
+    pub sol_keypair_path: Option<Secret>,
+    /// Encrypted keystore (`solbot keystore import`); takes precedence over `sol_keypair_path`.
+    pub keystore_path: Option<Secret>,
+    pub keystore_passphrase_file: Option<Secret>,
+    /// `local` (keystore / keypair in-process) or `remote` (HTTP signer).
+    pub signer: String,
+    pub signer_url: Option<String>,
+    pub signer_token: Option<Secret>,
 
     // Persistence
     pub state_path: String,
     pub heartbeat_log_path: String,
     pub trading_journal_path: String,
+    /// JSONL trade ledger (closes only for now); the journal is rendered from it.
+    pub trade_ledger_path: String,
+    pub equity_log_path: String,
+    /// Undelivered alerts, retried across restarts.
+    pub alert_outbox_path: String,
+    /// JSONL of alerts that failed to deliver.
+    pub alert_failed_log_path: String,
+
+    // Journal
+    /// Bundled journal template: `es` or `en`.
+    pub journal_lang: String,
+    /// TOML file whose keys override the bundled template.
+    pub journal_template_path: Option<String>,
+
+    // Strategies (file-only nested sections, plus a few env overrides)
+    pub strategies: StrategiesConfig,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StrategiesConfig {
+    pub momentum: MomentumConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MomentumConfig {
+    pub enabled: bool,
+    pub rsi_period: usize,
+    pub rsi_breakout: f64,
+    pub min_volume_usdc_1m: f64,
+    /// Base mints to scan.
+    pub watchlist: Vec<String>,
+}
+
+impl Default for MomentumConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            rsi_period: 14,
+            rsi_breakout: 60.0,
+            min_volume_usdc_1m: 50_000.0,
+            watchlist: vec![],
+        }
+    }
+}
+
+/// A scalar that can come from an env var or a TOML value.
+trait ConfigValue: Sized {
+    const KIND: &'static str;
+    fn from_env(raw: &str) -> Option<Self>;
+    fn from_toml(v: &toml::Value) -> Option<Self>;
+}
+
+impl ConfigValue for bool {
+    const KIND: &'static str = "bool";
+    fn from_env(raw: &str) -> Option<Self> {
+        match raw.to_lowercase().as_str() {
+            "1" | "true" | "yes" | "y" | "on" => Some(true),
+            "0" | "false" | "no" | "n" | "off" => Some(false),
+            _ => None,
+        }
+    }
+    fn from_toml(v: &toml::Value) -> Option<Self> {
+        v.as_bool()
+    }
+}
+
+impl ConfigValue for f64 {
+    const KIND: &'static str = "number";
+    fn from_env(raw: &str) -> Option<Self> {
+        raw.parse().ok().filter(|x: &f64| x.is_finite())
+    }
+    fn from_toml(v: &toml::Value) -> Option<Self> {
+        v.as_float().or_else(|| v.as_integer().map(|i| i as f64))
+    }
+}
+
+impl ConfigValue for u64 {
+    const KIND: &'static str = "non-negative integer";
+    fn from_env(raw: &str) -> Option<Self> {
+        raw.parse().ok()
+    }
+    fn from_toml(v: &toml::Value) -> Option<Self> {
+        v.as_integer().and_then(|i| u64::try_from(i).ok())
+    }
+}
+
+impl ConfigValue for u32 {
+    const KIND: &'static str = "non-negative integer";
+    fn from_env(raw: &str) -> Option<Self> {
+        raw.parse().ok()
+    }
+    fn from_toml(v: &toml::Value) -> Option<Self> {
+        v.as_integer().and_then(|i| u32::try_from(i).ok())
+    }
 }
 
-fn env_bool(key: &str, default: bool) -> bool {
-    match std::env::var(key).ok().map(|s| s.trim().to_lowercase()) {
-        None => default,
-        Some(v) if v.is_empty() => default,
-        Some(v) if v == "1" || v == "true" || v == "yes" || v == "y" || v == "on" => true,
-        Some(v) if v == "0" || v == "false" || v == "no" || v == "n" || v == "off" => false,
-        Some(_) => default,
+impl ConfigValue for usize {
+    const KIND: &'static str = "non-negative integer";
+    fn from_env(raw: &str) -> Option<Self> {
+        raw.parse().ok()
+    }
+    fn from_toml(v: &toml::Value) -> Option<Self> {
+        v.as_integer().and_then(|i| usize::try_from(i).ok())
     }
 }
 
-fn env_parse<T: std::str::FromStr>(key: &str) -> Option<T> {
-    std::env::var(key).ok().and_then(|x| x.parse().ok())
+impl ConfigValue for String {
+    const KIND: &'static str = "string";
+    fn from_env(raw: &str) -> Option<Self> {
+        Some(raw.to_string())
+    }
+    fn from_toml(v: &toml::Value) -> Option<Self> {
+        v.as_str().map(str::to_string)
+    }
+}
+
+impl ConfigValue for Secret {
+    const KIND: &'static str = "string";
+    fn from_env(raw: &str) -> Option<Self> {
+        Some(Secret::new(raw))
+    }
+    fn from_toml(v: &toml::Value) -> Option<Self> {
+        v.as_str().map(Secret::new)
+    }
+}
+
+impl ConfigValue for Severity {
+    const KIND: &'static str = "severity (info, warning, critical)";
+    fn from_env(raw: &str) -> Option<Self> {
+        Severity::parse(raw)
+    }
+    fn from_toml(v: &toml::Value) -> Option<Self> {
+        v.as_str().and_then(Severity::parse)
+    }
+}
+
+/// Layered sources: config file (base merged with profile), then env overrides.
+/// Every invalid value is collected with its key instead of falling back silently.
+struct Sources<'a> {
+    file: toml::Table,
+    origin: String,
+    dotenv: &'a HashMap<String, String>,
+    seen: HashSet<String>,
+    errors: Vec<String>,
+}
+
+/// `key` from the process env, else from the re-read `.env` (see
+/// [`Config::load_with_dotenv`]): the same precedence as at boot, where
+/// `.env` never overrides a variable that is already set.
+pub(crate) fn env_nonempty(dotenv: &HashMap<String, String>, key: &str) -> Option<String> {
+    [std::env::var(key).ok(), dotenv.get(key).cloned()]
+        .into_iter()
+        .flatten()
+        .map(|s| s.trim().to_string())
+        .find(|s| !s.is_empty())
+}
+
+/// Deep-merges `over` into `base` (tables merge, everything else replaces).
+fn merge(base: &mut toml::Table, over: &toml::Table) {
+    for (k, v) in over {
+        match (base.get_mut(k), v) {
+            (Some(toml::Value::Table(b)), toml::Value::Table(o)) => merge(b, o),
+            _ => {
+                base.insert(k.clone(), v.clone());
+            }
+        }
+    }
+}
+
+fn leaf_keys(prefix: &str, t: &toml::Table, out: &mut Vec<String>) {
+    for (k, v) in t {
+        let path = if prefix.is_empty() { k.clone() } else { format!("{prefix}.{k}") };
+        match v {
+            toml::Value::Table(sub) => leaf_keys(&path, sub, out),
+            _ => out.push(path),
+        }
+    }
+}
+
+impl Sources<'_> {
+    fn lookup(&self, path: &str) -> Option<&toml::Value> {
+        let mut parts = path.split('.');
+        let mut v = self.file.get(parts.next()?)?;
+        for p in parts {
+            v = v.as_table()?.get(p)?;
+        }
+        Some(v)
+    }
+
+    /// Env vars (first non-empty wins) override the file value at `path`.
+    fn get<T: ConfigValue>(&mut self, path: &str, env: &[&str]) -> Option<T> {
+        self.seen.insert(path.to_string());
+
+        for key in env {
+            if let Some(raw) = env_nonempty(self.dotenv, key) {
+                return match T::from_env(&raw) {
+                    Some(v) => Some(v),
+                    None => {
+                        self.errors
+                            .push(format!("{key}: invalid value {raw:?} (expected {})", T::KIND));
+                        None
+                    }
+                };
+            }
+        }
+
+        let v = self.lookup(path)?;
+        match T::from_toml(v) {
+            Some(x) => Some(x),
+            None => {
+                self.errors.push(format!(
+                    "{path} ({}): invalid value {v} (expected {})",
+                    self.origin,
+                    T::KIND
+                ));
+                None
+            }
+        }
+    }
+
+    fn or<T: ConfigValue>(&mut self, path: &str, env: &[&str], default: T) -> T {
+        self.get(path, env).unwrap_or(default)
+    }
+
+    fn strategies(&mut self) -> StrategiesConfig {
+        let mut s = StrategiesConfig::default();
+
+        if let Some(v) = self.lookup("strategies.momentum").cloned() {
+            match v.try_into::<MomentumConfig>() {
+                Ok(m) => s.momentum = m,
+                Err(e) => self
+                    .errors
+                    .push(format!("strategies.momentum ({}): {}", self.origin, e.message())),
+            }
+        }
+
+        if let Some(enabled) = self.get::<bool>("strategies.momentum.enabled", &["SIE_MOMENTUM_ENABLED"]) {
+            s.momentum.enabled = enabled;
+        }
+        if let Some(raw) = env_nonempty(self.dotenv, "SIE_MOMENTUM_WATCHLIST") {
+            s.momentum.watchlist = raw
+                .split(',')
+                .map(str::trim)
+                .filter(|m| !m.is_empty())
+                .map(str::to_string)
+                .collect();
+        }
+        s
+    }
+
+    /// Keys present in the file that no field reads (typos, stale settings).
+    fn unknown_keys(&self) -> Vec<String> {
+        let mut keys = vec![];
+        leaf_keys("", &self.file, &mut keys);
+        keys.into_iter()
+            .filter(|k| !k.starts_with("strategies.momentum.") && !self.seen.contains(k))
+            .collect()
+    }
 }
 
 impl Config {
-    pub fn from_env() -> Result<Self> {
-        // RPC
-        let helius_http_url = std::env::var("HELIUS_HTTP_URL")
-            .or_else(|_| std::env::var("SIE_RPC_HTTP"))
-            .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string());
-        let helius_wss_url = std::env::var("HELIUS_WSS_URL")
-            .or_else(|_| std::env::var("SIE_RPC_WS"))
-            .unwrap_or_else(|_| "wss://api.mainnet-beta.solana.com".to_string());
-
-        let quicknode_http_url = std::env::var("QUICKNODE_HTTP_URL").ok();
-        let quicknode_wss_url = std::env::var("QUICKNODE_WSS_URL").ok();
-
-        // Alerts
-        let slack_webhook_url = std::env::var("SLACK_WEBHOOK_URL").ok();
-
-        // Runtime
-        let dry_run = env_bool("DRY_RUN", true);
-        let tz = std::env::var("SIE_TZ").unwrap_or_else(|_| "America/Buenos_Aires".to_string());
-
-        // Risk
-        let capital_usdc = env_parse::<f64>("SIE_CAPITAL_USDC").unwrap_or(200.0);
-        let position_size_usdc = env_parse::<f64>("SIE_POSITION_SIZE_USDC").unwrap_or(20.0);
-        let max_open_positions = env_parse::<usize>("MAX_OPEN_POSITIONS").unwrap_or(5);
-        let max_daily_loss_pct = env_parse::<f64>("SIE_MAX_DAILY_LOSS_PCT").unwrap_or(0.03);
-        let stop_loss_pct = env_parse::<f64>("SIE_STOP_LOSS_PCT").unwrap_or(0.10);
-        let take_profit_pct = env_parse::<f64>("SIE_TAKE_PROFIT_PCT").unwrap_or(0.40);
-        let trailing_arm_pct = env_parse::<f64>("SIE_TRAILING_ARM_PCT").unwrap_or(0.15);
-        let portfolio_hard_stop_pct = env_parse::<f64>("SIE_PORTFOLIO_HARD_STOP_PCT").unwrap_or(0.20);
-
-        if position_size_usdc <= 0.0 || capital_usdc <= 0.0 {
-            return Err(anyhow!("invalid capital/position size"));
+    /// Loads configuration in layers:
+    ///
+    /// 1. config file (`SIE_CONFIG`, default `./solbot.toml` if present)
+    /// 2. `[profiles.<SIE_PROFILE>]` from that file, merged over the base
+    /// 3. environment variables (including `.env`)
+    ///
+    /// Then runs [`Config::validate`]; any validation error fails the load.
+    pub fn load() -> Result<Self> {
+        let (cfg, report) = Self::load_with_report()?;
+        if report.has_errors() {
+            return Err(anyhow!("{report}"));
+        }
+        Ok(cfg)
+    }
+
+    /// Loads the layers and validates, returning warnings alongside the config.
+    pub fn load_with_report() -> Result<(Self, ValidationReport)> {
+        Self::load_with_dotenv(&HashMap::new())
+    }
+
+    /// Like [`Config::load_with_report`], with `dotenv` (a re-read `.env`)
+    /// filling in keys the process env doesn't set. Used on reload, where the
+    /// environment can't be updated safely.
+    pub fn load_with_dotenv(dotenv: &HashMap<String, String>) -> Result<(Self, ValidationReport)> {
+        let cfg = Self::load_layers_with(dotenv)?;
+        let report = cfg.validate();
+        Ok((cfg, report))
+    }
+
+    /// A signer is configured (remote, encrypted keystore or plaintext keypair).
+    pub fn has_signing_key(&self) -> bool {
+        self.signer == "remote" || self.keystore_path.is_some() || self.sol_keypair_path.is_some()
+    }
+
+    pub fn has_alert_sink(&self) -> bool {
+        self.slack_webhook_url.is_some()
+            || self.telegram_bot_token.is_some()
+            || self.discord_webhook_url.is_some()
+            || self.alert_webhook_url.is_some()
+    }
+
+    /// Logs the effective settings at startup. Secrets appear only as
+    /// fingerprints (and the host, for URLs).
+    pub fn log_summary(&self) {
+        info!(
+            profile = self.profile.as_deref().unwrap_or("-"),
+            dry_run = self.dry_run,
+            tz = %self.tz,
+            journal_lang = %self.journal_lang,
+            capital_usdc = self.capital_usdc,
+            position_size_usdc = self.position_size_usdc,
+            max_open_positions = self.max_open_positions,
+            max_daily_loss_pct = self.max_daily_loss_pct,
+            portfolio_hard_stop_pct = self.portfolio_hard_stop_pct,
+            slippage_bps = self.slippage_bps,
+            max_slippage_bps = self.max_slippage_bps,
+            jupiter = %self.jupiter_base_url,
+            control_addr = %self.control_addr,
+            monitoring_addr = %self.monitoring_addr,
+            state_path = %self.state_path,
+            "boot.config"
+        );
+        info!(
+            rpc_http = %self.helius_http_url.summary(),
+            rpc_wss = %self.helius_wss_url.summary(),
+            quicknode_http = %summarize(self.quicknode_http_url.as_ref()),
+            quicknode_wss = %summarize(self.quicknode_wss_url.as_ref()),
+            slack_webhook = %summarize(self.slack_webhook_url.as_ref()),
+            telegram_bot_token = %summarize(self.telegram_bot_token.as_ref()),
+            discord_webhook = %summarize(self.discord_webhook_url.as_ref()),
+            alert_webhook = %summarize(self.alert_webhook_url.as_ref()),
+            control_tokens = %summarize(self.control_tokens.as_ref()),
+            slack_signing_secret = %summarize(self.slack_signing_secret.as_ref()),
+            sol_keypair = %summarize(self.sol_keypair_path.as_ref()),
+            keystore = %summarize(self.keystore_path.as_ref()),
+            signer = %self.signer,
+            signer_url = %self.signer_url.as_deref().unwrap_or("-"),
+            signer_token = %summarize(self.signer_token.as_ref()),
+            "boot.secrets"
+        );
+    }
+
+    /// Reads the layers only (no cross-field validation).
+    /// All invalid values and unknown file keys are reported together.
+    pub fn load_layers() -> Result<Self> {
+        Self::load_layers_with(&HashMap::new())
+    }
+
+    fn load_layers_with(dotenv: &HashMap<String, String>) -> Result<Self> {
+        let explicit_path = env_nonempty(dotenv, "SIE_CONFIG");
+        let path = explicit_path.clone().unwrap_or_else(|| "./solbot.toml".to_string());
+        let profile = env_nonempty(dotenv, "SIE_PROFILE");
+
+        let file = if Path::new(&path).exists() {
+            let raw = std::fs::read_to_string(&path).with_context(|| format!("reading {path}"))?;
+            raw.parse::<toml::Table>().with_context(|| format!("parsing {path}"))?
+        } else if explicit_path.is_some() {
+            return Err(anyhow!("SIE_CONFIG: {path} not found"));
+        } else {
+            toml::Table::new()
+        };
+
+        Self::from_layers(file, &path, profile, dotenv)
+    }
+
+    /// Builds the config from the parsed `file` (read from `path`), its
+    /// `profile` section merged over the base, and env overrides on top.
+    fn from_layers(
+        mut file: toml::Table,
+        path: &str,
+        profile: Option<String>,
+        dotenv: &HashMap<String, String>,
+    ) -> Result<Self> {
+        let profiles = file.remove("profiles");
+        let mut origin = path.to_string();
+        if let Some(name) = profile.as_deref() {
+            let over = profiles
+                .as_ref()
+                .and_then(|p| p.get(name))
+                .and_then(|p| p.as_table())
+                .ok_or_else(|| anyhow!("SIE_PROFILE: profile '{name}' not found in {path}"))?;
+            merge(&mut file, over);
+            origin = format!("{path} [profile {name}]");
         }
 
-        // Execution
-        let jupiter_base_url = std::env::var("JUPITER_BASE_URL")
-            .unwrap_or_else(|_| "https://quote-api.jup.ag".to_string());
-        let slippage_bps = env_parse::<u64>("SIE_SLIPPAGE_BPS").unwrap_or(50);
-        let max_slippage_bps = env_parse::<u64>("SIE_MAX_SLIPPAGE_BPS").unwrap_or(100);
-        if slippage_bps > max_slippage_bps {
-            return Err(anyhow!("SIE_SLIPPAGE_BPS cannot exceed SIE_MAX_SLIPPAGE_BPS"));
+        let mut src = Sources {
+            file,
+            origin,
+            dotenv,
+            seen: HashSet::new(),
+            errors: vec![],
+        };
+
+        let cfg = Self {
+            profile,
+
+            // RPC
+            helius_http_url: src.or(
+                "rpc.helius_http_url",
+                &["HELIUS_HTTP_URL", "SIE_RPC_HTTP"],
+                Secret::new("https://api.mainnet-beta.solana.com"),
+            ),
+            helius_wss_url: src.or(
+                "rpc.helius_wss_url",
+                &["HELIUS_WSS_URL", "SIE_RPC_WS"],
+                Secret::new("wss://api.mainnet-beta.solana.com"),
+            ),
+            quicknode_http_url: src.get("rpc.quicknode_http_url", &["QUICKNODE_HTTP_URL"]),
+            quicknode_wss_url: src.get("rpc.quicknode_wss_url", &["QUICKNODE_WSS_URL"]),
+
+            // Alerts
+            slack_webhook_url: src.get("alerts.slack_webhook_url", &["SLACK_WEBHOOK_URL"]),
+            slack_min_severity: src.or("alerts.slack_min_severity", &["SIE_SLACK_MIN_SEVERITY"], Severity::Info),
+            telegram_bot_token: src.get("alerts.telegram_bot_token", &["TELEGRAM_BOT_TOKEN"]),
+            telegram_chat_id: src.get("alerts.telegram_chat_id", &["TELEGRAM_CHAT_ID"]),
+            telegram_min_severity: src.or(
+                "alerts.telegram_min_severity",
+                &["SIE_TELEGRAM_MIN_SEVERITY"],
+                Severity::Warning,
+            ),
+            discord_webhook_url: src.get("alerts.discord_webhook_url", &["DISCORD_WEBHOOK_URL"]),
+            discord_min_severity: src.or(
+                "alerts.discord_min_severity",
+                &["SIE_DISCORD_MIN_SEVERITY"],
+                Severity::Warning,
+            ),
+            alert_webhook_url: src.get("alerts.webhook_url", &["SIE_ALERT_WEBHOOK_URL"]),
+            alert_webhook_min_severity: src.or(
+                "alerts.webhook_min_severity",
+                &["SIE_ALERT_WEBHOOK_MIN_SEVERITY"],
+                Severity::Info,
+            ),
+            alert_suppress_secs: src.or("alerts.suppress_secs", &["SIE_ALERT_SUPPRESS_SECS"], 600),
+            alert_escalate_secs: src.or("alerts.escalate_secs", &["SIE_ALERT_ESCALATE_SECS"], 1800),
+            alert_max_attempts: src.or("alerts.max_attempts", &["SIE_ALERT_MAX_ATTEMPTS"], 12),
+            explorer_tx_url: src.or(
+                "alerts.explorer_tx_url",
+                &["SIE_EXPLORER_TX_URL"],
+                "https://solscan.io/tx/{sig}".to_string(),
+            ),
+            weekly_summary: src.or("alerts.weekly_summary", &["SIE_WEEKLY_SUMMARY"], false),
+
+            // Runtime
+            dry_run: src.or("runtime.dry_run", &["DRY_RUN"], true),
+            tz: src.or("runtime.tz", &["SIE_TZ"], "America/Buenos_Aires".to_string()),
+
+            control_addr: src.or("control.addr", &["SIE_CONTROL_ADDR"], "127.0.0.1:8787".to_string()),
+            control_tokens: src.get("control.tokens", &["SIE_CONTROL_TOKENS"]),
+
+            monitoring_addr: src.or("monitoring.addr", &["SIE_MONITORING_ADDR"], "127.0.0.1:9464".to_string()),
+            health_max_tick_age_secs: src.or(
+                "monitoring.max_tick_age_secs",
+                &["SIE_HEALTH_MAX_TICK_AGE_SECS"],
+                60,
+            ),
+            otlp_endpoint: src.get("monitoring.otlp_endpoint", &["SIE_OTLP_ENDPOINT"]),
+
+            chatops_addr: src.or("chatops.addr", &["SIE_CHATOPS_ADDR"], "127.0.0.1:8789".to_string()),
+            slack_signing_secret: src.get("chatops.slack_signing_secret", &["SLACK_SIGNING_SECRET"]),
+            slack_operators: src.get("chatops.slack_operators", &["SIE_SLACK_OPERATORS"]),
+            telegram_commands: src.or("chatops.telegram_commands", &["SIE_TELEGRAM_COMMANDS"], false),
+            telegram_operators: src.get("chatops.telegram_operators", &["SIE_TELEGRAM_OPERATORS"]),
+            chatops_confirm_secs: src.or("chatops.confirm_secs", &["SIE_CHATOPS_CONFIRM_SECS"], 60),
+
+            // Risk
+            capital_usdc: src.or("risk.capital_usdc", &["SIE_CAPITAL_USDC"], 200.0),
+            position_size_usdc: src.or("risk.position_size_usdc", &["SIE_POSITION_SIZE_USDC"], 20.0),
+            max_open_positions: src.or("risk.max_open_positions", &["MAX_OPEN_POSITIONS"], 5),
+            max_daily_loss_pct: src.or("risk.max_daily_loss_pct", &["SIE_MAX_DAILY_LOSS_PCT"], 0.03),
+            stop_loss_pct: src.or("risk.stop_loss_pct", &["SIE_STOP_LOSS_PCT"], 0.10),
+            take_profit_pct: src.or("risk.take_profit_pct", &["SIE_TAKE_PROFIT_PCT"], 0.40),
+            trailing_arm_pct: src.or("risk.trailing_arm_pct", &["SIE_TRAILING_ARM_PCT"], 0.15),
+            portfolio_hard_stop_pct: src.or(
+                "risk.portfolio_hard_stop_pct",
+                &["SIE_PORTFOLIO_HARD_STOP_PCT"],
+                0.20,
+            ),
+            force_reduce_on_daily_loss: src.or(
+                "risk.force_reduce_on_daily_loss",
+                &["SIE_FORCE_REDUCE_ON_DAILY_LOSS"],
+                false,
+            ),
+            equity_interval_secs: src.or("risk.equity_interval_secs", &["SIE_EQUITY_INTERVAL_SECS"], 60),
+
+            // Execution
+            jupiter_base_url: src.or(
+                "execution.jupiter_base_url",
+                &["JUPITER_BASE_URL"],
+                "https://quote-api.jup.ag".to_string(),
+            ),
+            market_data_url: src.or(
+                "execution.market_data_url",
+                &["SIE_MARKET_DATA_URL"],
+                "https://api.dexscreener.com".to_string(),
+            ),
+            slippage_bps: src.or("execution.slippage_bps", &["SIE_SLIPPAGE_BPS"], 50),
+            max_slippage_bps: src.or("execution.max_slippage_bps", &["SIE_MAX_SLIPPAGE_BPS"], 100),
+
+            sol_keypair_path: src.get("keys.sol_keypair_path", &["SOL_KEYPAIR_PATH"]),
+            keystore_path: src.get("keys.keystore_path", &["SIE_KEYSTORE_PATH"]),
+            keystore_passphrase_file: src.get("keys.passphrase_file", &["SIE_KEYSTORE_PASSPHRASE_FILE"]),
+            signer: src.or("keys.signer", &["SIE_SIGNER"], "local".to_string()),
+            signer_url: src.get("keys.signer_url", &["SIE_SIGNER_URL"]),
+            signer_token: src.get("keys.signer_token", &["SIE_SIGNER_TOKEN"]),
+
+            state_path: src.or("paths.state", &["SIE_STATE_PATH"], "./state.json".to_string()),
+            heartbeat_log_path: src.or("paths.heartbeat_log", &["SIE_HEARTBEAT_LOG"], "./heartbeat.log".to_string()),
+            trading_journal_path: src.or("paths.trading_journal", &["SIE_TRADING_MD"], "./docs/trading.md".to_string()),
+            trade_ledger_path: src.or("paths.trade_ledger", &["SIE_TRADE_LEDGER"], "./trades.jsonl".to_string()),
+            equity_log_path: src.or("paths.equity_log", &["SIE_EQUITY_LOG"], "./equity.jsonl".to_string()),
+            alert_outbox_path: src.or("paths.alert_outbox", &["SIE_ALERT_OUTBOX"], "./alerts_outbox.json".to_string()),
+            alert_failed_log_path: src.or(
+                "paths.alert_failed_log",
+                &["SIE_ALERT_FAILED_LOG"],
+                "./alerts_failed.jsonl".to_string(),
+            ),
+
+            journal_lang: src.or("journal.lang", &["SIE_JOURNAL_LANG"], "es".to_string()),
+            journal_template_path: src.get("journal.template", &["SIE_JOURNAL_TEMPLATE"]),
+
+            strategies: src.strategies(),
+        };
+
+        for k in src.unknown_keys() {
+            src.errors.push(format!("{k} ({}): unknown key", src.origin));
+        }
+
+        if !src.errors.is_empty() {
+            return Err(anyhow!(
+                "invalid configuration ({} problem(s)):\n  - {}",
+                src.errors.len(),
+                src.errors.join("\n  - ")
+            ));
         }
 
-        let sol_keypair_path = std::env::var("SOL_KEYPAIR_PATH").ok();
-
-        let state_path = std::env::var("SIE_STATE_PATH").unwrap_or_else(|_| "./state.json".to_string());
-        let heartbeat_log_path = std::env::var("SIE_HEARTBEAT_LOG").unwrap_or_else(|_| "./heartbeat.log".to_string());
-        let trading_journal_path = std::env::var("SIE_TRADING_MD").unwrap_or_else(|_| "./docs/trading.md".to_string());
-
-        Ok(Self {
-            helius_http_url,
-            helius_wss_url,
-            quicknode_http_url,
-            quicknode_wss_url,
-            slack_webhook_url,
-            dry_run,
-            tz,
-            capital_usdc,
-            position_size_usdc,
-            max_open_positions,
-            max_daily_loss_pct,
-            stop_loss_pct,
-            take_profit_pct,
-            trailing_arm_pct,
-            portfolio_hard_stop_pct,
-            jupiter_base_url,
-            slippage_bps,
-            max_slippage_bps,
-            sol_keypair_path,
-            state_path,
-            heartbeat_log_path,
-            trading_journal_path,
-        })
+        Ok(cfg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = r#"
+[risk]
+capital_usdc = 500.0
+position_size_usdc = 25.0
+stop_loss_pct = 0.08
+
+[runtime]
+dry_run = false
+
+[profiles.paper.risk]
+position_size_usdc = 10.0
+
+[profiles.paper.runtime]
+dry_run = true
+"#;
+
+    fn load(file: &str, profile: Option<&str>, env: &[(&str, &str)]) -> Result<Config> {
+        let env: HashMap<String, String> = env.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        Config::from_layers(file.parse().unwrap(), "test.toml", profile.map(str::to_string), &env)
+    }
+
+    #[test]
+    fn profile_overrides_the_file_and_env_overrides_both() {
+        let base = load(FILE, None, &[]).unwrap();
+        assert_eq!(base.position_size_usdc, 25.0);
+        assert!(!base.dry_run);
+        assert_eq!(base.max_open_positions, 5, "unset keys keep their default");
+
+        let paper = load(FILE, Some("paper"), &[]).unwrap();
+        assert_eq!(paper.position_size_usdc, 10.0);
+        assert!(paper.dry_run);
+        assert_eq!(paper.capital_usdc, 500.0, "the profile merges over the base");
+        assert_eq!(paper.stop_loss_pct, 0.08);
+
+        let env = load(FILE, Some("paper"), &[("SIE_POSITION_SIZE_USDC", "15")]).unwrap();
+        assert_eq!(env.position_size_usdc, 15.0);
+        assert_eq!(env.profile.as_deref(), Some("paper"));
+    }
+
+    #[test]
+    fn missing_profile_is_an_error() {
+        let err = load(FILE, Some("live"), &[]).unwrap_err().to_string();
+        assert!(err.contains("profile 'live' not found in test.toml"), "{err}");
+    }
+
+    #[test]
+    fn unknown_keys_and_bad_values_are_reported_together() {
+        let file = r#"
+[risk]
+stop_los_pct = 0.1
+capital_usdc = "lots"
+
+[strategies.momentum]
+enabled = true
+"#;
+        let err = load(file, None, &[("SIE_TAKE_PROFIT_PCT", "abc")])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("3 problem(s)"), "{err}");
+        assert!(err.contains("risk.stop_los_pct (test.toml): unknown key"), "{err}");
+        assert!(err.contains("risk.capital_usdc (test.toml): invalid value \"lots\" (expected number)"), "{err}");
+        assert!(err.contains("SIE_TAKE_PROFIT_PCT: invalid value \"abc\" (expected number)"), "{err}");
+        assert!(!err.contains("strategies.momentum"), "{err}");
     }
 }
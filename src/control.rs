@@ -0,0 +1,493 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use axum::extract::{Path, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use tracing::{error, info, warn};
+
+use crate::engine::{Engine, SwapResult};
+use crate::exits::{Claim, CloseOutcome, ExitContext};
+use crate::notifier::{Event, Notifier, Severity};
+use crate::reload::LiveParams;
+use crate::risk::{BotMode, ExitReason, RiskParams};
+use crate::state::{ModeChange, PersistedState, Position, StateStore};
+
+/// Applies an operator mode override to `st` and records it in the audit trail.
+pub fn apply_mode_change(
+    st: &mut PersistedState,
+    params: &RiskParams,
+    to: BotMode,
+    operator: &str,
+    reason: &str,
+) -> Result<ModeChange> {
+    if reason.trim().is_empty() {
+        return Err(anyhow!("a reason is required"));
+    }
+
+    let from = st.risk.mode;
+    st.risk.operator_set_mode(params, to)?;
+    st.sync_mode_from_risk();
+
+    let change = ModeChange {
+        ts: chrono::Utc::now().to_rfc3339(),
+        from,
+        to,
+        operator: operator.to_string(),
+        reason: reason.trim().to_string(),
+    };
+    st.mode_history.push(change.clone());
+    Ok(change)
+}
+
+/// Parses `name:token,name:token` into token -> operator name.
+pub fn parse_operator_tokens(raw: &str) -> Result<HashMap<String, String>> {
+    let mut out = HashMap::new();
+    for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (name, token) = entry
+            .split_once(':')
+            .ok_or_else(|| anyhow!("SIE_CONTROL_TOKENS entry must be name:token"))?;
+        if name.is_empty() || token.len() < 16 {
+            return Err(anyhow!(
+                "SIE_CONTROL_TOKENS entry for '{name}' needs a name and a token of at least 16 chars"
+            ));
+        }
+        out.insert(token.to_string(), name.to_string());
+    }
+    if out.is_empty() {
+        return Err(anyhow!("SIE_CONTROL_TOKENS is empty"));
+    }
+    Ok(out)
+}
+
+// --- operations (shared by the HTTP API and chat commands) ---
+
+/// Operator actions against the daemon's state and engine. Callers are
+/// responsible for authenticating `operator`.
+#[derive(Clone)]
+pub struct Control {
+    store_path: String,
+    engine: Engine,
+    notifier: Notifier,
+    params: watch::Receiver<LiveParams>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ModeView {
+    pub mode: BotMode,
+    pub day_key: String,
+    pub daily_pnl_usdc: f64,
+    pub daily_loss_limit_usdc: f64,
+    pub recent_changes: Vec<ModeChange>,
+    #[serde(default)]
+    pub open_positions: usize,
+    #[serde(default)]
+    pub equity_usdc: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SetModeRequest {
+    pub mode: BotMode,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CloseRequest {
+    pub reason: String,
+}
+
+/// Result of one manual close.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CloseResult {
+    pub id: String,
+    pub base_mint: String,
+    pub signature: Option<String>,
+    pub pnl_usdc: Option<f64>,
+    pub error: Option<String>,
+}
+
+pub struct ApiError(pub StatusCode, pub String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        ApiError(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())
+    }
+}
+
+impl Control {
+    pub fn new(engine: Engine, notifier: Notifier, params: watch::Receiver<LiveParams>) -> Self {
+        Self {
+            store_path: engine.cfg.state_path.clone(),
+            engine,
+            notifier,
+            params,
+        }
+    }
+
+    fn risk_params(&self) -> RiskParams {
+        self.params.borrow().risk.clone()
+    }
+
+    pub fn engine(&self) -> &Engine {
+        &self.engine
+    }
+
+    pub async fn mode(&self) -> Result<ModeView, ApiError> {
+        let _guard = crate::state::lock().await;
+        let st = load_state(&StateStore::new(&self.store_path))?;
+
+        let n = st.mode_history.len();
+        Ok(ModeView {
+            mode: st.risk.mode,
+            day_key: st.risk.daily.day_key.clone(),
+            daily_pnl_usdc: st.risk.daily_total_pnl_usdc(),
+            daily_loss_limit_usdc: self.risk_params().daily_loss_limit_usdc(),
+            recent_changes: st.mode_history[n.saturating_sub(20)..].to_vec(),
+            open_positions: st.positions.len(),
+            equity_usdc: st.risk.equity_usdc,
+        })
+    }
+
+    pub async fn positions(&self) -> Result<Vec<Position>, ApiError> {
+        let _guard = crate::state::lock().await;
+        Ok(load_state(&StateStore::new(&self.store_path))?.positions)
+    }
+
+    pub async fn set_mode(&self, to: BotMode, operator: &str, reason: &str) -> Result<ModeChange, ApiError> {
+        let change = {
+            let _guard = crate::state::lock().await;
+            let store = StateStore::new(&self.store_path);
+            let mut st = load_state(&store)?;
+            let change = apply_mode_change(&mut st, &self.risk_params(), to, operator, reason)
+                .map_err(|e| ApiError(StatusCode::CONFLICT, e.to_string()))?;
+            store.save(&st)?;
+            change
+        };
+
+        info!(from = ?change.from, to = ?change.to, operator = %change.operator, reason = %change.reason, "control.mode_change");
+        self.notifier.event(Event::ModeChanged((&change).into()));
+        Ok(change)
+    }
+
+    /// Closes the given positions (all open ones when `id` is `None`) through the
+    /// same exit path as automatic exits, with `ExitReason::Manual`.
+    ///
+    /// The state lock is held only to pick the targets and to book each close;
+    /// the swaps run without it, on positions claimed so the loops leave them
+    /// alone. A position whose sell is already pending is reported as such.
+    pub async fn close(&self, id: Option<&str>, operator: &str, reason: &str) -> Result<Vec<CloseResult>, ApiError> {
+        if reason.trim().is_empty() {
+            return Err(ApiError(StatusCode::BAD_REQUEST, "a reason is required".into()));
+        }
+
+        let store = StateStore::new(&self.store_path);
+        let targets: Vec<(Position, Option<Claim>)> = {
+            let _guard = crate::state::lock().await;
+            let st = load_state(&store)?;
+            let picked: Vec<&Position> = match id {
+                Some(id) => match st.positions.iter().find(|p| p.id == id) {
+                    Some(p) => vec![p],
+                    None => return Err(ApiError(StatusCode::NOT_FOUND, format!("no open position {id}"))),
+                },
+                None => st.positions.iter().collect(),
+            };
+            picked
+                .into_iter()
+                .map(|p| {
+                    let claim = if p.pending_sell.is_none() { crate::exits::claim(&p.id) } else { None };
+                    (p.clone(), claim)
+                })
+                .collect()
+        };
+
+        let risk_params = self.risk_params();
+        let ctx = ExitContext {
+            engine: &self.engine,
+            notifier: &self.notifier,
+            risk_params: &risk_params,
+            trading_journal_path: &self.engine.cfg.trading_journal_path,
+            trade_ledger_path: &self.engine.cfg.trade_ledger_path,
+        };
+        let note = format!("manual close by {operator}: {}", reason.trim());
+
+        let mut results = vec![];
+        for (p, claim) in targets {
+            let (id, base_mint) = (p.id.clone(), p.base_mint.clone());
+            if let Some(pending) = &p.pending_sell {
+                // Already closing: the positions loop settles it.
+                results.push(CloseResult {
+                    id,
+                    base_mint,
+                    signature: Some(pending.signature.clone()),
+                    pnl_usdc: None,
+                    error: Some("sell already pending; position kept until it lands or expires".into()),
+                });
+                continue;
+            }
+            let Some(claim) = claim else {
+                results.push(CloseResult {
+                    id,
+                    base_mint,
+                    signature: None,
+                    pnl_usdc: None,
+                    error: Some("already being closed by another request".into()),
+                });
+                continue;
+            };
+            info!(%id, %operator, reason = %reason.trim(), "control.manual_close");
+
+            let price = crate::exits::market_price(&ctx, &p).await;
+            let r = match crate::exits::send_sell(&ctx, &p, price).await {
+                Ok(r) => r,
+                Err(e) => {
+                    self.notifier
+                        .alert(Severity::Critical, &format!("[SIE] MANUAL CLOSE failed for {id} ({base_mint}): {e}"));
+                    results.push(CloseResult {
+                        id,
+                        base_mint,
+                        signature: None,
+                        pnl_usdc: None,
+                        error: Some(e.to_string()),
+                    });
+                    continue;
+                }
+            };
+
+            let signature = r.signature.clone();
+            let booked = self.record_manual_sell(&ctx, &store, &id, price, &note, r).await;
+            drop(claim);
+            match booked {
+                Ok(CloseOutcome::Closed { signature, pnl_usdc }) => results.push(CloseResult {
+                    id,
+                    base_mint,
+                    signature: Some(signature),
+                    pnl_usdc: Some(pnl_usdc),
+                    error: None,
+                }),
+                Ok(CloseOutcome::Pending { signature }) => results.push(CloseResult {
+                    id,
+                    base_mint,
+                    signature: Some(signature),
+                    pnl_usdc: None,
+                    error: Some("sell sent but unconfirmed; position kept until it lands or expires".into()),
+                }),
+                Err(e) => {
+                    // The tokens are sold (or on their way) but the close is not in state.
+                    error!(%id, %signature, error = %e, "control.manual_close_unbooked");
+                    self.notifier.alert(
+                        Severity::Critical,
+                        &format!("[SIE] MANUAL CLOSE of {id} ({base_mint}) sent {signature} but could not be booked: {e}"),
+                    );
+                    results.push(CloseResult {
+                        id,
+                        base_mint,
+                        signature: None,
+                        pnl_usdc: None,
+                        error: Some(format!("sell {signature} sent but not booked: {e}")),
+                    });
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Books a manual sell under the state lock (see [`crate::exits::record_sell`]).
+    async fn record_manual_sell(
+        &self,
+        ctx: &ExitContext<'_>,
+        store: &StateStore,
+        id: &str,
+        price: f64,
+        note: &str,
+        r: SwapResult,
+    ) -> Result<CloseOutcome> {
+        let _guard = crate::state::lock().await;
+        let mut st = store.load()?.ok_or_else(|| anyhow!("state.json missing"))?;
+        let idx = st
+            .positions
+            .iter()
+            .position(|p| p.id == id)
+            .ok_or_else(|| anyhow!("position {id} no longer in state"))?;
+        let outcome = crate::exits::record_sell(ctx, &mut st, idx, ExitReason::Manual, price, note, r).await;
+        store.save(&st)?;
+        Ok(outcome)
+    }
+}
+
+// --- server (runs inside the daemon) ---
+
+#[derive(Clone)]
+pub struct ControlServer {
+    control: Control,
+    operators: Arc<HashMap<String, String>>,
+}
+
+impl ControlServer {
+    pub fn new(control: Control, operators: HashMap<String, String>) -> Self {
+        Self {
+            control,
+            operators: Arc::new(operators),
+        }
+    }
+
+    /// Resolves the operator identity from `Authorization: Bearer <token>`.
+    fn operator(&self, headers: &HeaderMap) -> Result<String, ApiError> {
+        let token = headers
+            .get(axum::http::header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .ok_or_else(|| ApiError(StatusCode::UNAUTHORIZED, "missing bearer token".into()))?;
+
+        self.operators
+            .iter()
+            .find(|(t, _)| ct_eq(t.as_bytes(), token.as_bytes()))
+            .map(|(_, name)| name.clone())
+            .ok_or_else(|| {
+                warn!("control.auth.rejected");
+                ApiError(StatusCode::UNAUTHORIZED, "invalid token".into())
+            })
+    }
+}
+
+/// Constant-time comparison for bearer tokens.
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn load_state(store: &StateStore) -> Result<PersistedState, ApiError> {
+    store
+        .load()?
+        .ok_or_else(|| ApiError(StatusCode::SERVICE_UNAVAILABLE, "state.json not initialized yet".into()))
+}
+
+async fn get_mode(State(srv): State<ControlServer>, headers: HeaderMap) -> Result<Json<ModeView>, ApiError> {
+    srv.operator(&headers)?;
+    Ok(Json(srv.control.mode().await?))
+}
+
+async fn set_mode(
+    State(srv): State<ControlServer>,
+    headers: HeaderMap,
+    Json(req): Json<SetModeRequest>,
+) -> Result<Json<ModeChange>, ApiError> {
+    let operator = srv.operator(&headers)?;
+    Ok(Json(srv.control.set_mode(req.mode, &operator, &req.reason).await?))
+}
+
+async fn close_one(
+    State(srv): State<ControlServer>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(req): Json<CloseRequest>,
+) -> Result<Json<Vec<CloseResult>>, ApiError> {
+    let operator = srv.operator(&headers)?;
+    Ok(Json(srv.control.close(Some(&id), &operator, &req.reason).await?))
+}
+
+async fn close_all(
+    State(srv): State<ControlServer>,
+    headers: HeaderMap,
+    Json(req): Json<CloseRequest>,
+) -> Result<Json<Vec<CloseResult>>, ApiError> {
+    let operator = srv.operator(&headers)?;
+    Ok(Json(srv.control.close(None, &operator, &req.reason).await?))
+}
+
+/// Serves the control API until the listener fails. Bind to loopback only.
+pub async fn serve(addr: &str, srv: ControlServer) -> Result<()> {
+    let app = Router::new()
+        .route("/v1/mode", get(get_mode).post(set_mode))
+        .route("/v1/positions/close-all", post(close_all))
+        .route("/v1/positions/:id/close", post(close_one))
+        .with_state(srv);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!(%addr, "control.api.listening");
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+// --- client (used by the CLI) ---
+
+pub struct ControlClient {
+    base_url: String,
+    token: String,
+    http: Client,
+}
+
+impl ControlClient {
+    pub fn new(base_url: String, token: String) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token,
+            http: Client::new(),
+        }
+    }
+
+    async fn read<T: for<'de> Deserialize<'de>>(resp: reqwest::Response) -> Result<T> {
+        let status = resp.status();
+        if !status.is_success() {
+            let body: serde_json::Value = resp.json().await.unwrap_or_default();
+            return Err(anyhow!(
+                "control api {status}: {}",
+                body["error"].as_str().unwrap_or("unknown error")
+            ));
+        }
+        Ok(resp.json().await?)
+    }
+
+    pub async fn mode(&self) -> Result<ModeView> {
+        let resp = self
+            .http
+            .get(format!("{}/v1/mode", self.base_url))
+            .bearer_auth(&self.token)
+            .send()
+            .await?;
+        Self::read(resp).await
+    }
+
+    /// Closes one position, or all of them when `id` is `None`.
+    pub async fn close(&self, id: Option<&str>, reason: &str) -> Result<Vec<CloseResult>> {
+        let url = match id {
+            Some(id) => format!("{}/v1/positions/{id}/close", self.base_url),
+            None => format!("{}/v1/positions/close-all", self.base_url),
+        };
+        let resp = self
+            .http
+            .post(url)
+            .bearer_auth(&self.token)
+            .json(&CloseRequest {
+                reason: reason.to_string(),
+            })
+            .send()
+            .await?;
+        Self::read(resp).await
+    }
+
+    pub async fn set_mode(&self, mode: BotMode, reason: &str) -> Result<ModeChange> {
+        let resp = self
+            .http
+            .post(format!("{}/v1/mode", self.base_url))
+            .bearer_auth(&self.token)
+            .json(&SetModeRequest {
+                mode,
+                reason: reason.to_string(),
+            })
+            .send()
+            .await?;
+        Self::read(resp).await
+    }
+}
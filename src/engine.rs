@@ -1,20 +1,47 @@
 use anyhow::{anyhow, Result};
 use base64::Engine as _;
+use serde::{Deserialize, Serialize};
 use solana_client::nonblocking::rpc_client::RpcClient;
-use solana_client::rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig};
+use solana_client::rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig, RpcTransactionConfig};
+use solana_client::rpc_request::TokenAccountsFilter;
 use solana_sdk::commitment_config::CommitmentConfig;
-use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
 use solana_sdk::transaction::VersionedTransaction;
-use tracing::{info, warn};
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{UiTransactionEncoding, UiTransactionTokenBalance};
+use tracing::{info, info_span, warn, Instrument, Span};
+
+use std::sync::Arc;
+use std::time::Duration;
 
 use crate::config::Config;
-use crate::jupiter::{ensure_slippage_bounds, JupiterClient, QuoteRequest, SwapRequest};
+use crate::firewall::{lookup_table_addresses, resolve_accounts, TxFirewall, LOOKUP_TABLE_PROGRAM};
+use crate::jupiter::{ensure_slippage_bounds, JupiterClient, QuoteRequest, QuoteResponse, SwapRequest};
+use crate::market::{MarketClient, MarketContext, SOL_MINT};
+use crate::signer::{SignContext, TxSigner};
+
+fn ui_amount(base_units: u128, decimals: u32) -> f64 {
+    base_units as f64 / 10f64.powi(decimals as i32)
+}
+
+/// How long to wait for a sent swap to reach `confirmed`, and how often to check.
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
+const CONFIRM_POLL: Duration = Duration::from_millis(750);
+
+/// Market context is best effort: never hold an exit up for long.
+const MARKET_TIMEOUT: Duration = Duration::from_secs(5);
 
 #[derive(Clone)]
 pub struct Engine {
     pub cfg: Config,
     rpc: RpcClient,
     jup: JupiterClient,
+    /// `None` when `market_data_url` is empty.
+    market: Option<MarketClient>,
+    /// Set up once at startup; `None` when no key is configured (dry-run).
+    signer: Option<Arc<dyn TxSigner>>,
 }
 
 #[derive(Debug, Clone)]
@@ -24,42 +51,120 @@ pub struct SwapPlan {
     /// base units
     pub in_amount: u64,
     pub slippage_bps: u64,
+    /// Estimated USDC value, for signer policy.
+    pub notional_usdc: f64,
 }
 
 #[derive(Debug, Clone)]
 pub struct SwapResult {
     pub signature: String,
+    pub fill: Fill,
+    /// Seen `confirmed` (always true in dry-run). When false the transaction
+    /// was sent but may still land or be dropped: see [`Engine::tx_status`].
+    pub landed: bool,
+    /// Last block height at which the transaction's blockhash is valid.
+    pub last_valid_block_height: Option<u64>,
+}
+
+/// Where a sent transaction stands.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TxStatus {
+    Landed,
+    /// Included, but failed on-chain.
+    Failed(String),
+    /// Not seen yet; it can still land.
+    Pending,
+    /// Not seen and its blockhash expired: it will never land.
+    Expired,
+}
+
+/// What a swap traded, in UI units of each mint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fill {
+    pub in_amount: f64,
+    /// Output Jupiter quoted for the route.
+    pub quoted_out_amount: f64,
+    /// Output actually received, from the confirmed transaction. `None` in
+    /// dry-run or when the transaction could not be read.
+    pub out_amount: Option<f64>,
+    /// Network fee (base + priority), from the transaction.
+    pub fee_lamports: Option<u64>,
+    /// Priority fee Jupiter put in the transaction.
+    pub priority_fee_lamports: Option<u64>,
+}
+
+impl Fill {
+    /// Received amount, or the quote when the fill is unknown.
+    pub fn out_or_quoted(&self) -> f64 {
+        self.out_amount.unwrap_or(self.quoted_out_amount)
+    }
+
+    /// Output per unit of input.
+    pub fn price(&self) -> Option<f64> {
+        (self.in_amount > 0.0).then(|| self.out_or_quoted() / self.in_amount)
+    }
+
+    /// Shortfall of the fill against the quote, in bps (negative = better than quoted).
+    pub fn slippage_bps(&self) -> Option<f64> {
+        let out = self.out_amount?;
+        (self.quoted_out_amount > 0.0).then(|| (self.quoted_out_amount - out) / self.quoted_out_amount * 10_000.0)
+    }
 }
 
 impl Engine {
-    pub fn new(cfg: Config) -> Self {
-        let rpc = RpcClient::new_with_commitment(cfg.helius_http_url.clone(), CommitmentConfig::confirmed());
+    pub fn new(cfg: Config, signer: Option<Arc<dyn TxSigner>>) -> Self {
+        let rpc = RpcClient::new_with_commitment(cfg.helius_http_url.expose().to_string(), CommitmentConfig::confirmed());
         let jup = JupiterClient::new(cfg.jupiter_base_url.clone());
-        Self { cfg, rpc, jup }
+        let market = (!cfg.market_data_url.is_empty()).then(|| MarketClient::new(cfg.market_data_url.clone()));
+        Self {
+            cfg,
+            rpc,
+            jup,
+            market,
+            signer,
+        }
     }
 
-    fn load_keypair(&self) -> Result<Keypair> {
-        let path = self
-            .cfg
-            .sol_keypair_path
+    fn signer(&self) -> Result<&dyn TxSigner> {
+        self.signer
             .as_deref()
-            .ok_or_else(|| anyhow!("SOL_KEYPAIR_PATH is required"))?;
-        Ok(read_keypair_file(path)?)
+            .ok_or_else(|| anyhow!("no signer (SIE_SIGNER=remote, SIE_KEYSTORE_PATH or SOL_KEYPAIR_PATH)"))
     }
 
-    /// Jupiter quote -> build swap -> simulateTransaction (mandatory) -> send.
+    /// Jupiter quote -> build swap -> simulateTransaction (mandatory) -> send -> confirm.
     pub async fn execute_swap(&self, plan: SwapPlan) -> Result<SwapResult> {
+        let m = crate::metrics::get();
+        m.swap_stage("attempted");
+        let span = info_span!(
+            "swap",
+            input_mint = %plan.input_mint,
+            output_mint = %plan.output_mint,
+            in_amount = plan.in_amount,
+            slippage_bps = plan.slippage_bps,
+            notional_usdc = plan.notional_usdc,
+            dry_run = self.cfg.dry_run,
+            out_amount = tracing::field::Empty,
+            priority_fee_micro_lamports = tracing::field::Empty,
+            signature = tracing::field::Empty,
+        );
+        let res = self.swap(plan).instrument(span).await;
+        if res.is_err() {
+            m.swap_stage("failed");
+        }
+        res
+    }
+
+    async fn swap(&self, plan: SwapPlan) -> Result<SwapResult> {
         ensure_slippage_bounds(plan.slippage_bps, self.cfg.max_slippage_bps)?;
 
         info!(?plan, dry_run = self.cfg.dry_run, "engine.execute_swap");
 
         // DRY_RUN still performs quote building but does not sign/send.
-        let kp = self.load_keypair()?;
-        let user_pubkey = kp.pubkey();
+        let signer = self.signer()?;
+        let user_pubkey = signer.pubkey();
 
         // 1) Quote
         let quote = self
-            .jup
             .quote(QuoteRequest {
                 input_mint: plan.input_mint.clone(),
                 output_mint: plan.output_mint.clone(),
@@ -67,32 +172,82 @@ impl Engine {
                 slippage_bps: plan.slippage_bps,
                 only_direct_routes: None,
             })
+            .instrument(info_span!("swap.quote"))
             .await?;
+        Span::current().record("out_amount", quote.out_amount.as_str());
+        let quoted_out: u64 = quote
+            .out_amount
+            .parse()
+            .map_err(|_| anyhow!("invalid jupiter outAmount"))?;
+        let in_dec = self.mint_decimals(&plan.input_mint).await?;
+        let out_dec = self.mint_decimals(&plan.output_mint).await?;
 
         // 2) Priority fee (best-effort)
-        let compute_unit_price_micro_lamports = self.dynamic_priority_fee_micro_lamports().await.ok();
+        let compute_unit_price_micro_lamports = self
+            .dynamic_priority_fee_micro_lamports()
+            .instrument(info_span!("swap.priority_fee"))
+            .await
+            .ok();
+        if let Some(price) = compute_unit_price_micro_lamports {
+            crate::metrics::get().priority_fee_micro_lamports.set(price as i64);
+            Span::current().record("priority_fee_micro_lamports", price);
+        }
 
         // 3) Swap tx from Jupiter
-        let swap = self
-            .jup
-            .swap(SwapRequest {
+        let swap = crate::metrics::rpc(
+            "jupiter.swap",
+            self.jup.swap(SwapRequest {
                 quote_response: quote.rest,
                 user_public_key: user_pubkey.to_string(),
                 wrap_and_unwrap_sol: Some(true),
                 compute_unit_price_micro_lamports,
-            })
-            .await?;
+            }),
+        )
+        .instrument(info_span!("swap.build"))
+        .await?;
+        // Jupiter reports the priority fee it put in the transaction.
+        let priority_fee_lamports = swap.rest["prioritizationFeeLamports"].as_u64();
+        let mut fill = Fill {
+            in_amount: ui_amount(plan.in_amount as u128, in_dec),
+            quoted_out_amount: ui_amount(quoted_out as u128, out_dec),
+            out_amount: None,
+            fee_lamports: None,
+            priority_fee_lamports,
+        };
 
         let tx_bytes = base64::engine::general_purpose::STANDARD.decode(swap.swap_transaction)?;
         let mut vtx: VersionedTransaction = bincode::deserialize(&tx_bytes)?;
 
-        // Sign (Jupiter provides message; we add our sig)
-        vtx.sign(&[&kp], self.rpc.get_latest_blockhash().await?)?;
+        // Pre-sign firewall: only a plain swap into our own accounts gets signed.
+        self.check_firewall(&vtx, user_pubkey, &plan)
+            .instrument(info_span!("swap.firewall"))
+            .await?;
+
+        // Sign (Jupiter provides message; we add our sig) over a fresh blockhash;
+        // its last valid height tells when an unconfirmed send can no longer land.
+        let (blockhash, last_valid_block_height) = crate::metrics::rpc(
+            "getLatestBlockhash",
+            self.rpc.get_latest_blockhash_with_commitment(CommitmentConfig::confirmed()),
+        )
+        .await?;
+        let last_valid_block_height = Some(last_valid_block_height);
+        vtx.message.set_recent_blockhash(blockhash);
+        let vtx = signer
+            .sign(
+                vtx,
+                &SignContext {
+                    input_mint: plan.input_mint.clone(),
+                    output_mint: plan.output_mint.clone(),
+                    notional_usdc: plan.notional_usdc,
+                },
+            )
+            .instrument(info_span!("swap.sign"))
+            .await?;
 
         // 4) simulateTransaction (mandatory)
-        let sim = self
-            .rpc
-            .simulate_transaction_with_config(
+        let sim = crate::metrics::rpc(
+            "simulateTransaction",
+            self.rpc.simulate_transaction_with_config(
                 &vtx,
                 RpcSimulateTransactionConfig {
                     sig_verify: false,
@@ -100,44 +255,220 @@ impl Engine {
                     commitment: Some(CommitmentConfig::processed()),
                     ..RpcSimulateTransactionConfig::default()
                 },
-            )
-            .await?;
+            ),
+        )
+        .instrument(info_span!("swap.simulate"))
+        .await?;
 
         if let Some(err) = sim.value.err {
             return Err(anyhow!("simulateTransaction failed: {err:?}"));
         }
+        crate::metrics::get().swap_stage("simulated");
 
         if self.cfg.dry_run {
             info!("dry_run: simulation ok, skipping send");
             return Ok(SwapResult {
                 signature: "DRY_RUN".into(),
+                fill,
+                landed: true,
+                last_valid_block_height,
             });
         }
 
         // 5) Send
-        let sig = self
-            .rpc
-            .send_transaction_with_config(
+        let sig = crate::metrics::rpc(
+            "sendTransaction",
+            self.rpc.send_transaction_with_config(
                 &vtx,
                 RpcSendTransactionConfig {
                     skip_preflight: true, // we already simulated
                     preflight_commitment: Some(CommitmentConfig::processed().commitment),
                     ..RpcSendTransactionConfig::default()
                 },
-            )
-            .await?;
+            ),
+        )
+        .instrument(info_span!("swap.send"))
+        .await?;
+        Span::current().record("signature", sig.to_string().as_str());
+
+        // The fee is paid once the transaction is included, whether it landed or not.
+        let m = crate::metrics::get();
+        if let Some(fee) = priority_fee_lamports {
+            m.priority_fee_lamports.inc_by(fee);
+        }
+
+        // 6) Confirm
+        let landed = self.confirm(&sig).instrument(info_span!("swap.confirm")).await?;
+        if landed {
+            m.swap_stage("landed");
+            self.read_fill(&sig, &user_pubkey, &plan.output_mint, out_dec, &mut fill)
+                .instrument(info_span!("swap.fill"))
+                .await;
+        } else {
+            // Sent but not seen yet; it may still land, so don't report a failed swap.
+            m.swap_stage("unconfirmed");
+            warn!(%sig, timeout_secs = CONFIRM_TIMEOUT.as_secs(), "engine.confirm_timeout");
+        }
 
         Ok(SwapResult {
             signature: sig.to_string(),
+            fill,
+            landed,
+            last_valid_block_height,
         })
     }
 
+    /// Fills in the received amount and fee of a landed swap (best effort).
+    async fn read_fill(&self, sig: &Signature, owner: &Pubkey, output_mint: &str, out_dec: u32, fill: &mut Fill) {
+        match self.received(sig, owner, output_mint, out_dec).await {
+            Ok((out, fee)) => {
+                fill.out_amount = Some(out);
+                fill.fee_lamports = Some(fee);
+            }
+            Err(e) => warn!(error = %e, %sig, "engine.fill_unavailable"),
+        }
+    }
+
+    /// Like the fill of [`Engine::execute_swap`], for a swap that landed after
+    /// its confirm timed out.
+    pub async fn landed_fill(&self, signature: &str, output_mint: &str, fill: &mut Fill) -> Result<()> {
+        let sig: Signature = signature.parse()?;
+        let owner = self.signer()?.pubkey();
+        let out_dec = self.mint_decimals(output_mint).await?;
+        self.read_fill(&sig, &owner, output_mint, out_dec, fill).await;
+        Ok(())
+    }
+
+    /// Status of a sent transaction, searching history so an old landing is
+    /// still found. Without `last_valid_block_height` it never reports `Expired`.
+    pub async fn tx_status(&self, signature: &str, last_valid_block_height: Option<u64>) -> Result<TxStatus> {
+        let sig: Signature = signature.parse()?;
+        let resp = crate::metrics::rpc(
+            "getSignatureStatuses",
+            self.rpc.get_signature_statuses_with_history(&[sig]),
+        )
+        .await?;
+        if let Some(Some(status)) = resp.value.first() {
+            if let Some(err) = &status.err {
+                return Ok(TxStatus::Failed(err.to_string()));
+            }
+            if status.satisfies_commitment(CommitmentConfig::confirmed()) {
+                return Ok(TxStatus::Landed);
+            }
+            return Ok(TxStatus::Pending);
+        }
+        if let Some(last_valid) = last_valid_block_height {
+            let height = crate::metrics::rpc("getBlockHeight", self.rpc.get_block_height()).await?;
+            if height > last_valid {
+                return Ok(TxStatus::Expired);
+            }
+        }
+        Ok(TxStatus::Pending)
+    }
+
+    /// Output received by `owner` (UI units) and the network fee, read from the
+    /// confirmed transaction's balance changes.
+    async fn received(&self, sig: &Signature, owner: &Pubkey, output_mint: &str, out_dec: u32) -> Result<(f64, u64)> {
+        let tx = crate::metrics::rpc(
+            "getTransaction",
+            self.rpc.get_transaction_with_config(
+                sig,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Json),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                },
+            ),
+        )
+        .await?;
+        let meta = tx
+            .transaction
+            .meta
+            .ok_or_else(|| anyhow!("transaction {sig} has no status meta"))?;
+
+        if output_mint == crate::balance::WSOL_MINT {
+            // Unwrapped to native SOL: the fee payer's lamport change, fee added back.
+            let (Some(pre), Some(post)) = (meta.pre_balances.first(), meta.post_balances.first()) else {
+                return Err(anyhow!("transaction {sig} has no balances"));
+            };
+            let lamports = *post as i128 - *pre as i128 + meta.fee as i128;
+            return Ok((lamports as f64 / LAMPORTS_PER_SOL as f64, meta.fee));
+        }
+
+        let owner = owner.to_string();
+        let held = |balances: OptionSerializer<Vec<UiTransactionTokenBalance>>| -> u128 {
+            Option::<Vec<_>>::from(balances)
+                .unwrap_or_default()
+                .iter()
+                .filter(|b| b.mint == output_mint && Option::<&String>::from(b.owner.as_ref()) == Some(&owner))
+                .map(|b| b.ui_token_amount.amount.parse::<u128>().unwrap_or(0))
+                .sum()
+        };
+        let delta = held(meta.post_token_balances).saturating_sub(held(meta.pre_token_balances));
+        Ok((ui_amount(delta, out_dec), meta.fee))
+    }
+
+    /// Waits until `sig` is confirmed (`Ok(true)`) or [`CONFIRM_TIMEOUT`] passes
+    /// (`Ok(false)`). Errors if the transaction failed on-chain.
+    async fn confirm(&self, sig: &Signature) -> Result<bool> {
+        let deadline = tokio::time::Instant::now() + CONFIRM_TIMEOUT;
+        loop {
+            match crate::metrics::rpc("getSignatureStatuses", self.rpc.get_signature_statuses(&[*sig])).await {
+                Ok(resp) => {
+                    if let Some(Some(status)) = resp.value.first() {
+                        if let Some(err) = &status.err {
+                            return Err(anyhow!("swap tx {sig} failed on-chain: {err}"));
+                        }
+                        if status.satisfies_commitment(CommitmentConfig::confirmed()) {
+                            return Ok(true);
+                        }
+                    }
+                }
+                // Keep polling; the transaction is already out.
+                Err(e) => warn!(error = %e, %sig, "engine.confirm.poll_failed"),
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Ok(false);
+            }
+            tokio::time::sleep(CONFIRM_POLL).await;
+        }
+    }
+
+    /// Decodes the swap message (resolving address lookup tables) and rejects
+    /// anything outside the [`TxFirewall`] policy, with the reason.
+    async fn check_firewall(&self, vtx: &VersionedTransaction, wallet: Pubkey, plan: &SwapPlan) -> Result<()> {
+        let alt_program: Pubkey = LOOKUP_TABLE_PROGRAM.parse()?;
+        let mut tables = vec![];
+        for l in vtx.message.address_table_lookups().unwrap_or_default() {
+            let acc = crate::metrics::rpc("getAccountInfo", self.rpc.get_account(&l.account_key)).await?;
+            if acc.owner != alt_program {
+                return Err(anyhow!("tx firewall: {} is not an address lookup table", l.account_key));
+            }
+            tables.push(
+                lookup_table_addresses(&acc.data)
+                    .ok_or_else(|| anyhow!("tx firewall: malformed lookup table {}", l.account_key))?,
+            );
+        }
+
+        let verdict = TxFirewall::new(wallet, &[&plan.input_mint, &plan.output_mint, crate::balance::WSOL_MINT])
+            .and_then(|fw| {
+                let accounts = resolve_accounts(&vtx.message, &tables)?;
+                fw.inspect(&vtx.message, &accounts)
+            });
+        if let Err(v) = verdict {
+            warn!(violation = %v, ?plan, "engine.firewall.rejected");
+            return Err(anyhow!("tx firewall rejected swap: {v}"));
+        }
+        Ok(())
+    }
+
     /// Best-effort dynamic priority fee.
     ///
     /// Returns micro-lamports per CU.
     async fn dynamic_priority_fee_micro_lamports(&self) -> Result<u64> {
         // Not all RPCs support getRecentPrioritizationFees. We keep it best-effort.
-        let fees = self.rpc.get_recent_prioritization_fees(&[]).await?;
+        let fees =
+            crate::metrics::rpc("getRecentPrioritizationFees", self.rpc.get_recent_prioritization_fees(&[])).await?;
         let Some(p) = fees.iter().map(|f| f.prioritization_fee).max() else {
             return Ok(0);
         };
@@ -146,35 +477,59 @@ impl Engine {
         Ok(micro)
     }
 
+    /// SOL and `base_mint` market stats for the journal. Best effort: a source
+    /// that fails or is slow is left out (`None` when both are).
+    pub async fn market_context(&self, base_mint: &str) -> Option<MarketContext> {
+        let market = self.market.as_ref()?;
+        let stats = |mint: &str| {
+            let mint = mint.to_string();
+            async move {
+                let res = tokio::time::timeout(
+                    MARKET_TIMEOUT,
+                    crate::metrics::rpc("market.token_stats", market.token_stats(&mint)),
+                )
+                .await;
+                match res {
+                    Ok(Ok(s)) => s,
+                    Ok(Err(e)) => {
+                        warn!(mint = %mint, error = %e, "engine.market_stats_failed");
+                        None
+                    }
+                    Err(_) => {
+                        warn!(mint = %mint, "engine.market_stats_timeout");
+                        None
+                    }
+                }
+            }
+        };
+        let (sol, token) = tokio::join!(stats(SOL_MINT), stats(base_mint));
+        (sol.is_some() || token.is_some()).then_some(MarketContext { sol, token })
+    }
+
     /// Returns the price as: quote_amount / base_amount for 1 base token.
     ///
     /// Uses Jupiter quote and Solana RPC to resolve decimals.
     pub async fn price_quote_per_base(&self, base_mint: &str, quote_mint: &str) -> Result<f64> {
         // Resolve decimals via RPC (token supply).
-        let base_supply = self
-            .rpc
-            .get_token_supply(&base_mint.parse()?)
-            .await?
-            .value;
-        let quote_supply = self
-            .rpc
-            .get_token_supply(&quote_mint.parse()?)
-            .await?
-            .value;
-
-        let base_dec = base_supply.decimals as u32;
-        let quote_dec = quote_supply.decimals as u32;
+        let base_dec = self.mint_decimals(base_mint).await?;
 
         let one_base_units: u64 = 10u64
             .checked_pow(base_dec)
             .ok_or_else(|| anyhow!("base decimals too large"))?;
 
+        self.quote_out_ui(base_mint, quote_mint, one_base_units).await // per 1 base
+    }
+
+    /// Value of `in_amount` base units of `input_mint`, in UI units of `output_mint`,
+    /// as quoted by Jupiter for a full-size exit (includes price impact).
+    pub async fn quote_out_ui(&self, input_mint: &str, output_mint: &str, in_amount: u64) -> Result<f64> {
+        let out_dec = self.mint_decimals(output_mint).await?;
+
         let quote = self
-            .jup
             .quote(QuoteRequest {
-                input_mint: base_mint.to_string(),
-                output_mint: quote_mint.to_string(),
-                amount: one_base_units.to_string(),
+                input_mint: input_mint.to_string(),
+                output_mint: output_mint.to_string(),
+                amount: in_amount.to_string(),
                 slippage_bps: self.cfg.slippage_bps,
                 only_direct_routes: None,
             })
@@ -185,8 +540,56 @@ impl Engine {
             .parse()
             .map_err(|_| anyhow!("invalid jupiter outAmount"))?;
 
-        let out_quote = out_amount as f64 / 10f64.powi(quote_dec as i32);
-        Ok(out_quote) // per 1 base
+        Ok(out_amount as f64 / 10f64.powi(out_dec as i32))
+    }
+
+    /// Jupiter quote, timed for the quote latency histogram.
+    async fn quote(&self, req: QuoteRequest) -> Result<QuoteResponse> {
+        let _timer = crate::metrics::get().quote_latency.start_timer();
+        crate::metrics::rpc("jupiter.quote", self.jup.quote(req)).await
+    }
+
+    /// Mint decimals via RPC (token supply).
+    pub async fn mint_decimals(&self, mint: &str) -> Result<u32> {
+        let supply = crate::metrics::rpc("getTokenSupply", self.rpc.get_token_supply(&mint.parse()?)).await?;
+        Ok(supply.decimals as u32)
+    }
+
+    /// Current slot; doubles as an RPC reachability probe.
+    pub async fn rpc_slot(&self) -> Result<u64> {
+        Ok(crate::metrics::rpc("getSlot", self.rpc.get_slot()).await?)
+    }
+
+    /// Public key of the trading wallet.
+    pub fn wallet_pubkey(&self) -> Result<Pubkey> {
+        Ok(self.signer()?.pubkey())
+    }
+
+    /// Native SOL balance of `owner` (UI units).
+    pub async fn sol_balance(&self, owner: &Pubkey) -> Result<f64> {
+        let lamports = crate::metrics::rpc("getBalance", self.rpc.get_balance(owner)).await?;
+        Ok(lamports as f64 / LAMPORTS_PER_SOL as f64)
+    }
+
+    /// Sum of all SPL token accounts of `owner` for `mint` (UI units).
+    pub async fn token_balance_ui(&self, owner: &Pubkey, mint: &str) -> Result<f64> {
+        let accounts = crate::metrics::rpc(
+            "getTokenAccountsByOwner",
+            self.rpc
+                .get_token_accounts_by_owner(owner, TokenAccountsFilter::Mint(mint.parse()?)),
+        )
+        .await?;
+
+        let mut total = 0.0;
+        for a in accounts {
+            // jsonParsed layout: { parsed: { info: { tokenAmount: { uiAmountString } } } }
+            let data = serde_json::to_value(&a.account.data)?;
+            let ui = data["parsed"]["info"]["tokenAmount"]["uiAmountString"]
+                .as_str()
+                .ok_or_else(|| anyhow!("unexpected token account encoding for {}", a.pubkey))?;
+            total += ui.parse::<f64>()?;
+        }
+        Ok(total)
     }
 
     /// Emergency helper: closes a single position (market exit base->quote).
@@ -195,12 +598,14 @@ impl Engine {
         base_mint: String,
         quote_mint: String,
         base_amount: u64,
+        notional_usdc: f64,
     ) -> Result<SwapResult> {
         self.execute_swap(SwapPlan {
             input_mint: base_mint,
             output_mint: quote_mint,
             in_amount: base_amount,
             slippage_bps: self.cfg.slippage_bps,
+            notional_usdc,
         })
         .await
     }
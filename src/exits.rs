@@ -0,0 +1,450 @@
+use std::collections::BTreeSet;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use tracing::{error, info, warn};
+
+use crate::engine::{Engine, Fill, SwapResult, TxStatus};
+use crate::notifier::events::{ModeChanged, TradeClosed};
+use crate::ledger::{LedgerEvent, TradeExit};
+use crate::notifier::{Event, Notifier, Severity};
+use crate::risk::{BotMode, ExitReason, RiskEvent, RiskParams};
+use crate::state::{PendingSell, PersistedState, Position};
+
+/// Strategy tag for ledger entries, until positions carry their own.
+const STRATEGY: &str = "momentum-scalping";
+
+/// Everything a position exit needs besides the state itself.
+pub struct ExitContext<'a> {
+    pub engine: &'a Engine,
+    pub notifier: &'a Notifier,
+    pub risk_params: &'a RiskParams,
+    pub trading_journal_path: &'a str,
+    pub trade_ledger_path: &'a str,
+}
+
+#[derive(Debug, Clone)]
+pub enum CloseOutcome {
+    /// Sold and booked; the position is gone from state.
+    Closed { signature: String, pnl_usdc: f64 },
+    /// Sell sent but not confirmed; the position stays with a [`PendingSell`].
+    Pending { signature: String },
+}
+
+/// Positions claimed by a close that swaps outside the state lock (manual
+/// closes). The loops leave them alone until the claim is dropped.
+static CLOSING: std::sync::Mutex<BTreeSet<String>> = std::sync::Mutex::new(BTreeSet::new());
+
+/// Claim on a position being closed; released on drop.
+pub struct Claim(String);
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        CLOSING.lock().expect("closing lock").remove(&self.0);
+    }
+}
+
+/// Claims position `id` for a close; `None` if another close holds it.
+pub fn claim(id: &str) -> Option<Claim> {
+    CLOSING
+        .lock()
+        .expect("closing lock")
+        .insert(id.to_string())
+        .then(|| Claim(id.to_string()))
+}
+
+/// Whether a close outside the state lock holds position `id`.
+pub fn is_closing(id: &str) -> bool {
+    CLOSING.lock().expect("closing lock").contains(id)
+}
+
+/// Pending sells without a known expiry height (state written before it was
+/// recorded) are given up after this long.
+const PENDING_MAX_AGE_SECS: i64 = 180;
+
+/// Updates peak/trailing state for `price` and returns the exit rule it triggers, if any.
+pub fn exit_signal(p: &mut Position, price: f64) -> Option<ExitReason> {
+    if price > p.peak_price {
+        p.peak_price = price;
+    }
+
+    let pnl_pct = (price - p.entry_price) / p.entry_price;
+    if !p.trailing_armed && pnl_pct >= p.trailing_arm_pct {
+        p.trailing_armed = true;
+    }
+
+    let stop_price = if p.trailing_armed {
+        p.peak_price * (1.0 - p.stop_loss_pct)
+    } else {
+        p.entry_price * (1.0 - p.stop_loss_pct)
+    };
+    let tp_price = p.entry_price * (1.0 + p.take_profit_pct);
+
+    if price <= stop_price {
+        Some(if p.trailing_armed {
+            ExitReason::TrailingStop
+        } else {
+            ExitReason::StopLoss
+        })
+    } else if price >= tp_price {
+        Some(ExitReason::TakeProfit)
+    } else {
+        None
+    }
+}
+
+/// Like [`close_position`], but fetches the current price first (see [`market_price`]).
+pub async fn close_at_market(
+    ctx: &ExitContext<'_>,
+    st: &mut PersistedState,
+    idx: usize,
+    reason: ExitReason,
+    note: &str,
+) -> Result<CloseOutcome> {
+    let price = market_price(ctx, &st.positions[idx]).await;
+    close_position(ctx, st, idx, reason, price, note).await
+}
+
+/// Current price of `p`. If it cannot be fetched this is the entry price, and
+/// the exit still goes through (PnL comes from the fill).
+pub async fn market_price(ctx: &ExitContext<'_>, p: &Position) -> f64 {
+    match ctx.engine.price_quote_per_base(&p.base_mint, &p.quote_mint).await {
+        Ok(px) => px,
+        Err(e) => {
+            warn!(error = %e, id = %p.id, "exits.price_unavailable_booking_at_entry");
+            p.entry_price
+        }
+    }
+}
+
+/// Market-exits `st.positions[idx]` (base -> quote), books the realized PnL
+/// from the sell fill, records the close in the ledger and journal and removes
+/// the position from state. `price` is the mark that triggered the exit.
+///
+/// If the sell is sent but not confirmed in time, the position stays in state
+/// with a [`PendingSell`] that [`settle_pending_sell`] resolves on later ticks.
+/// On error the position is left untouched.
+#[tracing::instrument(
+    name = "exit",
+    skip_all,
+    fields(
+        position_id = %st.positions[idx].id,
+        base_mint = %st.positions[idx].base_mint,
+        reason = ?reason,
+        price,
+    )
+)]
+pub async fn close_position(
+    ctx: &ExitContext<'_>,
+    st: &mut PersistedState,
+    idx: usize,
+    reason: ExitReason,
+    price: f64,
+    note: &str,
+) -> Result<CloseOutcome> {
+    let p = &st.positions[idx];
+    if let Some(pending) = &p.pending_sell {
+        return Err(anyhow!("sell {} already pending", pending.signature));
+    }
+    let Some(_claim) = claim(&p.id) else {
+        return Err(anyhow!("position {} is already being closed", p.id));
+    };
+
+    let r = send_sell(ctx, p, price).await?;
+    Ok(record_sell(ctx, st, idx, reason, price, note, r).await)
+}
+
+/// Sends the market sell of `p` at mark `price`. Touches no state: pair it
+/// with [`record_sell`], and hold a [`claim`] in between if the state lock
+/// is released.
+pub async fn send_sell(ctx: &ExitContext<'_>, p: &Position, price: f64) -> Result<SwapResult> {
+    ctx.engine
+        .close_position_market(
+            p.base_mint.clone(),
+            p.quote_mint.clone(),
+            p.base_amount,
+            p.size_usdc * price / p.entry_price,
+        )
+        .await
+}
+
+/// Records the sell `r` of `st.positions[idx]`: books the close if it landed,
+/// otherwise keeps the position with a [`PendingSell`].
+pub async fn record_sell(
+    ctx: &ExitContext<'_>,
+    st: &mut PersistedState,
+    idx: usize,
+    reason: ExitReason,
+    price: f64,
+    note: &str,
+    r: SwapResult,
+) -> CloseOutcome {
+    if !r.landed {
+        // It may still land or be dropped: keep tracking the tokens until we know.
+        let signature = r.signature.clone();
+        let p = &mut st.positions[idx];
+        mark_pending(p, r, reason, note, price, Utc::now());
+        warn!(id = %p.id, %signature, "exits.sell_pending");
+        ctx.notifier.alert_keyed(
+            Severity::Warning,
+            &format!("sell_pending:{}", p.id),
+            &format!("[SIE] SELL {} sent but unconfirmed ({signature}); position kept until it lands or expires", p.base_mint),
+        );
+        return CloseOutcome::Pending { signature };
+    }
+
+    let pnl_usdc = book_close(ctx, st, idx, reason, price, note, r.signature.clone(), r.fill).await;
+    CloseOutcome::Closed {
+        signature: r.signature,
+        pnl_usdc,
+    }
+}
+
+/// Resolves the pending sell of `st.positions[idx]`: books the close once it
+/// landed (`Ok(true)`, position removed), or clears it if it failed or expired
+/// so the exit rules fire again (`Ok(false)`). Still in flight: `Ok(false)`.
+pub async fn settle_pending_sell(ctx: &ExitContext<'_>, st: &mut PersistedState, idx: usize) -> Result<bool> {
+    let p = &st.positions[idx];
+    let Some(pending) = p.pending_sell.clone() else {
+        return Ok(false);
+    };
+
+    let status = ctx
+        .engine
+        .tx_status(&pending.signature, pending.last_valid_block_height)
+        .await?;
+
+    match settlement(status, &pending, Utc::now()) {
+        Settlement::Wait => Ok(false),
+        Settlement::Book => {
+            info!(id = %p.id, signature = %pending.signature, "exits.sell_landed");
+            crate::metrics::get().swap_stage("landed");
+            let mut fill = pending.fill;
+            if let Err(e) = ctx.engine.landed_fill(&pending.signature, &p.quote_mint, &mut fill).await {
+                warn!(error = %e, signature = %pending.signature, "engine.fill_unavailable");
+            }
+            book_close(ctx, st, idx, pending.reason, pending.price, &pending.note, pending.signature, fill).await;
+            Ok(true)
+        }
+        Settlement::Rearm(why) => {
+            let p = &mut st.positions[idx];
+            p.pending_sell = None;
+            p.sell_tx = None;
+            warn!(id = %p.id, signature = %pending.signature, %why, "exits.sell_dropped");
+            ctx.notifier.alert_keyed(
+                Severity::Critical,
+                &format!("sell_failed:{}", p.base_mint),
+                &format!("[SIE] SELL {} {why} ({}); position still open, exits re-armed", p.base_mint, pending.signature),
+            );
+            Ok(false)
+        }
+    }
+}
+
+/// Records an unconfirmed sell on `p`; the position stays until it lands or expires.
+fn mark_pending(p: &mut Position, r: SwapResult, reason: ExitReason, note: &str, price: f64, now: DateTime<Utc>) {
+    p.sell_tx = Some(r.signature.clone());
+    p.pending_sell = Some(PendingSell {
+        signature: r.signature,
+        reason,
+        note: note.to_string(),
+        price,
+        fill: r.fill,
+        last_valid_block_height: r.last_valid_block_height,
+        sent_at: now.to_rfc3339(),
+    });
+}
+
+/// What a pending sell's transaction status means for its position.
+#[derive(Debug, PartialEq)]
+enum Settlement {
+    /// Still in flight.
+    Wait,
+    /// Landed: book the close.
+    Book,
+    /// Failed or expired: clear it so the exit rules fire again. Holds why.
+    Rearm(String),
+}
+
+/// Reads `status` for `pending` at `now`. Without a known expiry height the
+/// sell is given up [`PENDING_MAX_AGE_SECS`] after it was sent.
+fn settlement(status: TxStatus, pending: &PendingSell, now: DateTime<Utc>) -> Settlement {
+    match status {
+        TxStatus::Landed => Settlement::Book,
+        TxStatus::Failed(err) => Settlement::Rearm(format!("failed on-chain: {err}")),
+        TxStatus::Expired => Settlement::Rearm("expired without landing".into()),
+        TxStatus::Pending if pending.last_valid_block_height.is_none() => {
+            let age = DateTime::parse_from_rfc3339(&pending.sent_at)
+                .map(|t| now.signed_duration_since(t).num_seconds())
+                .unwrap_or(i64::MAX);
+            if age > PENDING_MAX_AGE_SECS {
+                Settlement::Rearm("expired without landing".into())
+            } else {
+                Settlement::Wait
+            }
+        }
+        TxStatus::Pending => Settlement::Wait,
+    }
+}
+
+/// Books a landed sell: removes the position, registers the realized PnL,
+/// notifies and writes the ledger and journal. Returns the PnL (USDC).
+#[allow(clippy::too_many_arguments)]
+async fn book_close(
+    ctx: &ExitContext<'_>,
+    st: &mut PersistedState,
+    idx: usize,
+    reason: ExitReason,
+    price: f64,
+    note: &str,
+    signature: String,
+    fill: Fill,
+) -> f64 {
+    let mut p = st.positions.remove(idx);
+    p.sell_tx = Some(signature);
+    p.pending_sell = None;
+
+    let mut exit = TradeExit::new(&p, STRATEGY, reason, note, price, fill);
+    exit.market = ctx.engine.market_context(&p.base_mint).await;
+    let (pnl_usdc, pnl_pct) = (exit.pnl_usdc, exit.pnl_pct);
+
+    let prev_mode = st.risk.mode;
+    let ev = st.risk.register_realized_pnl(ctx.risk_params, pnl_usdc);
+    st.sync_mode_from_risk();
+    ctx.notifier.event(Event::TradeClosed(TradeClosed {
+        position_id: p.id.clone(),
+        strategy: STRATEGY.into(),
+        base_mint: p.base_mint.clone(),
+        quote_mint: p.quote_mint.clone(),
+        size_usdc: p.size_usdc,
+        entry_price: p.entry_price,
+        exit_price: exit.exit_price,
+        pnl_usdc,
+        pnl_pct,
+        reason,
+        note: note.to_string(),
+        buy_tx: p.buy_tx.clone(),
+        sell_tx: p.sell_tx.clone(),
+        mode: st.risk.mode,
+    }));
+
+    // Ledger first (record of truth), then the Markdown rendering of it.
+    let event = LedgerEvent::Close(exit);
+    if let Err(e) = crate::ledger::append(ctx.trade_ledger_path, &event) {
+        error!(error = %e, id = %p.id, "ledger.write_failed");
+        ctx.notifier.alert_keyed(
+            Severity::Critical,
+            "ledger.write",
+            &format!("[SIE] trade ledger write failed ({}): {e}", p.id),
+        );
+    }
+    let journal = crate::journal::Format::from_config(&ctx.engine.cfg)
+        .and_then(|fmt| crate::journal::append_event(ctx.trading_journal_path, &event, &fmt));
+    if let Err(e) = journal {
+        warn!(error = %e, id = %p.id, "journal.write_failed");
+    }
+
+    // React to mode transitions.
+    if matches!(ev, RiskEvent::EnterReadOnly) {
+        ctx.notifier.event(Event::ModeChanged(ModeChanged::risk(
+            prev_mode,
+            BotMode::ReadOnly,
+            "daily loss limit reached",
+        )));
+    }
+    if matches!(ev, RiskEvent::EnterEmergencyStop) {
+        ctx.notifier.event(Event::ModeChanged(ModeChanged::risk(
+            prev_mode,
+            BotMode::EmergencyStop,
+            "portfolio hard stop reached",
+        )));
+        // Liquidate remaining positions ASAP (positions loop handles it)
+    }
+
+    pnl_usdc
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+
+    fn unconfirmed_sell(last_valid_block_height: Option<u64>) -> SwapResult {
+        SwapResult {
+            signature: "5ig".into(),
+            fill: Fill {
+                in_amount: 1000.0,
+                quoted_out_amount: 12.0,
+                out_amount: None,
+                fee_lamports: None,
+                priority_fee_lamports: None,
+            },
+            landed: false,
+            last_valid_block_height,
+        }
+    }
+
+    fn pending_after_timeout(last_valid_block_height: Option<u64>, sent_at: DateTime<Utc>) -> (Position, PendingSell) {
+        let mut p = Position {
+            id: "p1".into(),
+            entry_price: 0.01,
+            ..Position::default()
+        };
+        let r = unconfirmed_sell(last_valid_block_height);
+        mark_pending(&mut p, r, ExitReason::StopLoss, "auto-exit", 0.009, sent_at);
+        let pending = p.pending_sell.clone().unwrap();
+        (p, pending)
+    }
+
+    #[test]
+    fn sell_that_times_out_stays_pending_and_settles_once_it_lands() {
+        let sent = Utc::now();
+        let (p, pending) = pending_after_timeout(Some(1_000), sent);
+        assert_eq!(p.sell_tx.as_deref(), Some("5ig"));
+        assert_eq!(pending.reason, ExitReason::StopLoss);
+        assert_eq!(pending.price, 0.009);
+
+        // Later ticks: still in flight, then seen confirmed.
+        assert_eq!(settlement(TxStatus::Pending, &pending, sent + Duration::seconds(40)), Settlement::Wait);
+        assert_eq!(settlement(TxStatus::Landed, &pending, sent + Duration::seconds(45)), Settlement::Book);
+    }
+
+    #[test]
+    fn pending_sell_is_rearmed_when_it_fails_or_its_blockhash_expires() {
+        let sent = Utc::now();
+        let (_, pending) = pending_after_timeout(Some(1_000), sent);
+        assert_eq!(
+            settlement(TxStatus::Failed("custom program error: 0x1771".into()), &pending, sent),
+            Settlement::Rearm("failed on-chain: custom program error: 0x1771".into())
+        );
+        assert_eq!(
+            settlement(TxStatus::Expired, &pending, sent),
+            Settlement::Rearm("expired without landing".into())
+        );
+        // With a known expiry height the block height decides, not the age.
+        assert_eq!(settlement(TxStatus::Pending, &pending, sent + Duration::hours(1)), Settlement::Wait);
+    }
+
+    #[test]
+    fn pending_sell_without_expiry_height_expires_after_max_age() {
+        let sent = Utc::now();
+        let (_, pending) = pending_after_timeout(None, sent);
+        let at_max = sent + Duration::seconds(PENDING_MAX_AGE_SECS);
+        assert_eq!(settlement(TxStatus::Pending, &pending, at_max), Settlement::Wait);
+        assert_eq!(
+            settlement(TxStatus::Pending, &pending, at_max + Duration::seconds(1)),
+            Settlement::Rearm("expired without landing".into())
+        );
+    }
+
+    #[test]
+    fn a_claimed_position_cannot_be_claimed_again_until_released() {
+        let claim = claim("p-claim").unwrap();
+        assert!(is_closing("p-claim"));
+        assert!(super::claim("p-claim").is_none());
+        drop(claim);
+        assert!(!is_closing("p-claim"));
+        assert!(super::claim("p-claim").is_some());
+    }
+}
@@ -0,0 +1,426 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use sha2::{Digest, Sha256};
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+
+/// Programs a Jupiter swap is expected to call.
+pub const ALLOWED_PROGRAMS: &[&str] = &[
+    JUPITER_V6,
+    TOKEN_PROGRAM,
+    TOKEN_2022_PROGRAM,
+    ATA_PROGRAM,
+    COMPUTE_BUDGET_PROGRAM,
+    SYSTEM_PROGRAM,
+];
+
+pub const JUPITER_V6: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+pub const TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+pub const TOKEN_2022_PROGRAM: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+pub const ATA_PROGRAM: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+pub const COMPUTE_BUDGET_PROGRAM: &str = "ComputeBudget111111111111111111111111111111";
+pub const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
+pub const LOOKUP_TABLE_PROGRAM: &str = "AddressLookupTab1e1111111111111111111111111";
+
+/// Address lookup table accounts start with a 56-byte metadata header,
+/// followed by the stored addresses (32 bytes each).
+const LOOKUP_TABLE_META_SIZE: usize = 56;
+
+fn key(s: &str) -> Pubkey {
+    s.parse().expect("valid program id constant")
+}
+
+/// Why a transaction was refused.
+#[derive(Debug)]
+pub struct Violation {
+    pub instruction: Option<usize>,
+    pub program: Option<Pubkey>,
+    pub reason: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.instruction, self.program) {
+            (Some(i), Some(p)) => write!(f, "instruction {i} ({p}): {}", self.reason),
+            (Some(i), None) => write!(f, "instruction {i}: {}", self.reason),
+            _ => write!(f, "{}", self.reason),
+        }
+    }
+}
+
+impl std::error::Error for Violation {}
+
+/// Addresses stored in a lookup table account (`data` as returned by RPC).
+pub fn lookup_table_addresses(data: &[u8]) -> Option<Vec<Pubkey>> {
+    let body = data.get(LOOKUP_TABLE_META_SIZE..)?;
+    if body.len() % 32 != 0 {
+        return None;
+    }
+    Some(
+        body.chunks_exact(32)
+            .map(|c| Pubkey::new_from_array(c.try_into().expect("32-byte chunk")))
+            .collect(),
+    )
+}
+
+/// Full account list of a message: static keys, then addresses loaded from
+/// lookup tables (all writable, then all readonly), in runtime order.
+/// `tables[i]` holds the addresses of `message.address_table_lookups()[i]`.
+pub fn resolve_accounts(message: &VersionedMessage, tables: &[Vec<Pubkey>]) -> Result<Vec<Pubkey>, Violation> {
+    let mut keys = message.static_account_keys().to_vec();
+    let lookups = message.address_table_lookups().unwrap_or_default();
+    if lookups.len() != tables.len() {
+        return Err(violation(None, None, "lookup tables not resolved"));
+    }
+
+    let pick = |table: &Vec<Pubkey>, key: &Pubkey, idx: u8| {
+        table
+            .get(idx as usize)
+            .copied()
+            .ok_or_else(|| violation(None, None, format!("lookup table {key} has no index {idx}")))
+    };
+    for (l, t) in lookups.iter().zip(tables) {
+        for i in &l.writable_indexes {
+            keys.push(pick(t, &l.account_key, *i)?);
+        }
+    }
+    for (l, t) in lookups.iter().zip(tables) {
+        for i in &l.readonly_indexes {
+            keys.push(pick(t, &l.account_key, *i)?);
+        }
+    }
+    Ok(keys)
+}
+
+/// The wallet's associated token accounts for `mint`, under both token programs.
+pub fn token_accounts(wallet: &Pubkey, mint: &Pubkey) -> [Pubkey; 2] {
+    [key(TOKEN_PROGRAM), key(TOKEN_2022_PROGRAM)].map(|token_program| {
+        Pubkey::find_program_address(&[wallet.as_ref(), token_program.as_ref(), mint.as_ref()], &key(ATA_PROGRAM)).0
+    })
+}
+
+fn violation(instruction: Option<usize>, program: Option<Pubkey>, reason: impl Into<String>) -> Violation {
+    Violation {
+        instruction,
+        program,
+        reason: reason.into(),
+    }
+}
+
+/// Anchor instruction discriminator: sha256("global:<name>")[..8].
+pub(crate) fn anchor_discriminator(name: &str) -> [u8; 8] {
+    let digest = Sha256::digest(format!("global:{name}").as_bytes());
+    digest[..8].try_into().expect("8 bytes")
+}
+
+/// A Jupiter v6 swap instruction and the account positions we read.
+struct JupiterRoute {
+    name: &'static str,
+    /// User transfer authority (the wallet).
+    authority: usize,
+    /// User source token account.
+    source: usize,
+    /// User destination token account.
+    destination: usize,
+    /// Optional extra destination: ours or the Jupiter program id placeholder.
+    extra: Option<usize>,
+    /// Args end in `(out_amount, quoted_in_amount, ..)` instead of `(in_amount, quoted_out_amount, ..)`.
+    exact_out: bool,
+}
+
+const fn route(name: &'static str, authority: usize, source: usize, destination: usize, extra: Option<usize>, exact_out: bool) -> JupiterRoute {
+    JupiterRoute {
+        name,
+        authority,
+        source,
+        destination,
+        extra,
+        exact_out,
+    }
+}
+
+const JUPITER_ROUTES: &[JupiterRoute] = &[
+    route("route", 1, 2, 3, Some(4), false),
+    route("route_with_token_ledger", 1, 2, 3, Some(4), false),
+    route("exact_out_route", 1, 2, 3, Some(4), true),
+    route("shared_accounts_route", 2, 3, 6, None, false),
+    route("shared_accounts_route_with_token_ledger", 2, 3, 6, None, false),
+    route("shared_accounts_exact_out_route", 2, 3, 6, None, true),
+];
+
+/// Every route's args end with `.., u64, u64, slippage_bps: u16, platform_fee_bps: u8`.
+const ROUTE_TAIL_LEN: usize = 8 + 8 + 2 + 1;
+
+fn jupiter_route(data: &[u8]) -> Result<&'static JupiterRoute, String> {
+    let disc = data.get(..8).ok_or("jupiter instruction without discriminator")?;
+    JUPITER_ROUTES
+        .iter()
+        .find(|r| anchor_discriminator(r.name) == disc)
+        .ok_or_else(|| "unknown jupiter instruction".into())
+}
+
+/// Token accounts and input amount of one Jupiter swap instruction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JupiterSwap {
+    pub source: Pubkey,
+    pub destination: Pubkey,
+    /// Input amount in base units: `in_amount`, or `quoted_in_amount` for exact-out routes.
+    pub in_amount: u64,
+}
+
+/// Jupiter swap instructions of `message`, read from their accounts and args.
+/// `accounts` is the resolved account list (see [`resolve_accounts`]); an
+/// index it doesn't cover is an error.
+pub fn jupiter_swaps(message: &VersionedMessage, accounts: &[Pubkey]) -> Result<Vec<JupiterSwap>, Violation> {
+    let jupiter = key(JUPITER_V6);
+    let mut swaps = vec![];
+    for (i, ix) in message.instructions().iter().enumerate() {
+        if accounts.get(ix.program_id_index as usize) != Some(&jupiter) {
+            continue;
+        }
+        let err = |reason: String| violation(Some(i), Some(jupiter), reason);
+        let r = jupiter_route(&ix.data).map_err(err)?;
+        let account = |pos: usize, what: &str| {
+            ix.accounts
+                .get(pos)
+                .and_then(|a| accounts.get(*a as usize))
+                .copied()
+                .ok_or_else(|| err(format!("{} {what} account not resolved", r.name)))
+        };
+        let tail = ix
+            .data
+            .len()
+            .checked_sub(ROUTE_TAIL_LEN)
+            .filter(|start| *start >= 8)
+            .map(|start| &ix.data[start..])
+            .ok_or_else(|| err(format!("{} args too short", r.name)))?;
+        let amount = if r.exact_out { &tail[8..16] } else { &tail[..8] };
+        swaps.push(JupiterSwap {
+            source: account(r.source, "source")?,
+            destination: account(r.destination, "destination")?,
+            in_amount: u64::from_le_bytes(amount.try_into().expect("8 bytes")),
+        });
+    }
+    Ok(swaps)
+}
+
+/// Pre-sign inspector for Jupiter swap transactions.
+///
+/// Only allowlisted programs may be called, the fee payer must be our wallet,
+/// and every top-level instruction that moves tokens or lamports must send
+/// them to accounts we own (the wallet or its ATAs for the swap mints).
+pub struct TxFirewall {
+    wallet: Pubkey,
+    /// Wallet plus its ATAs for the swap mints (both token programs).
+    ours: HashSet<Pubkey>,
+    allowed: HashSet<Pubkey>,
+}
+
+impl TxFirewall {
+    pub fn new(wallet: Pubkey, mints: &[&str]) -> Result<Self, Violation> {
+        let mut ours = HashSet::from([wallet]);
+        for m in mints {
+            let mint: Pubkey = m
+                .parse()
+                .map_err(|_| violation(None, None, format!("invalid mint {m}")))?;
+            ours.extend(token_accounts(&wallet, &mint));
+        }
+
+        Ok(Self {
+            wallet,
+            ours,
+            allowed: ALLOWED_PROGRAMS.iter().map(|p| key(p)).collect(),
+        })
+    }
+
+    /// Checks `message` against the policy. `accounts` is the resolved
+    /// account list from [`resolve_accounts`].
+    pub fn inspect(&self, message: &VersionedMessage, accounts: &[Pubkey]) -> Result<(), Violation> {
+        if accounts.first() != Some(&self.wallet) {
+            return Err(violation(
+                None,
+                None,
+                format!(
+                    "fee payer {} is not our wallet {}",
+                    accounts.first().map(|k| k.to_string()).unwrap_or_default(),
+                    self.wallet
+                ),
+            ));
+        }
+
+        for (i, ix) in message.instructions().iter().enumerate() {
+            let program = *accounts
+                .get(ix.program_id_index as usize)
+                .ok_or_else(|| violation(Some(i), None, "program id index out of range"))?;
+            if !self.allowed.contains(&program) {
+                return Err(violation(Some(i), Some(program), "program not in allowlist"));
+            }
+
+            let mut ix_accounts = Vec::with_capacity(ix.accounts.len());
+            for a in &ix.accounts {
+                ix_accounts.push(
+                    *accounts
+                        .get(*a as usize)
+                        .ok_or_else(|| violation(Some(i), Some(program), format!("account index {a} out of range")))?,
+                );
+            }
+
+            self.check_instruction(&program.to_string(), &ix_accounts, &ix.data)
+                .map_err(|reason| violation(Some(i), Some(program), reason))?;
+        }
+        Ok(())
+    }
+
+    fn ours(&self, accounts: &[Pubkey], idx: usize, what: &str) -> Result<(), String> {
+        match accounts.get(idx) {
+            Some(k) if self.ours.contains(k) => Ok(()),
+            Some(k) => Err(format!("{what} {k} is not our wallet or one of its token accounts")),
+            None => Err(format!("missing {what} account")),
+        }
+    }
+
+    fn is_wallet(&self, accounts: &[Pubkey], idx: usize, what: &str) -> Result<(), String> {
+        match accounts.get(idx) {
+            Some(k) if *k == self.wallet => Ok(()),
+            Some(k) => Err(format!("{what} {k} is not our wallet")),
+            None => Err(format!("missing {what} account")),
+        }
+    }
+
+    fn check_instruction(&self, program: &str, accounts: &[Pubkey], data: &[u8]) -> Result<(), String> {
+        match program {
+            COMPUTE_BUDGET_PROGRAM => Ok(()),
+
+            SYSTEM_PROGRAM => match data.get(..4).map(|d| u32::from_le_bytes(d.try_into().unwrap())) {
+                // Transfer { lamports }: wrapping SOL into our wSOL account.
+                Some(2) => {
+                    self.is_wallet(accounts, 0, "transfer source")?;
+                    self.ours(accounts, 1, "transfer destination")
+                }
+                other => Err(format!("system instruction {other:?} not allowed")),
+            },
+
+            ATA_PROGRAM => match data.first() {
+                // Create / CreateIdempotent: [payer, ata, owner, mint, system, token program]
+                None | Some(0) | Some(1) => {
+                    self.is_wallet(accounts, 0, "ATA payer")?;
+                    self.is_wallet(accounts, 2, "ATA owner")
+                }
+                Some(d) => Err(format!("associated token instruction {d} not allowed")),
+            },
+
+            TOKEN_PROGRAM | TOKEN_2022_PROGRAM => match data.first() {
+                // Transfer: [source, destination, authority]
+                Some(3) => self.ours(accounts, 1, "token transfer destination"),
+                // TransferChecked: [source, mint, destination, authority]
+                Some(12) => self.ours(accounts, 2, "token transfer destination"),
+                // CloseAccount: [account, destination, owner]
+                Some(9) => {
+                    self.ours(accounts, 0, "closed account")?;
+                    self.is_wallet(accounts, 1, "close destination")
+                }
+                // SyncNative: [account]
+                Some(17) => self.ours(accounts, 0, "synced account"),
+                Some(d) => Err(format!("token instruction {d} not allowed")),
+                None => Err("empty token instruction".into()),
+            },
+
+            JUPITER_V6 => {
+                let r = jupiter_route(data)?;
+                self.is_wallet(accounts, r.authority, &format!("{} transfer authority", r.name))?;
+                self.ours(accounts, r.destination, &format!("{} destination", r.name))?;
+                if let Some(extra) = r.extra {
+                    // Optional account: Jupiter passes its own program id for "none".
+                    if accounts.get(extra) != Some(&key(JUPITER_V6)) {
+                        self.ours(accounts, extra, &format!("{} destination_token_account", r.name))?;
+                    }
+                }
+                Ok(())
+            }
+
+            _ => Err("program not in allowlist".into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+    use solana_sdk::compute_budget::ComputeBudgetInstruction;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::transaction::VersionedTransaction;
+
+    use super::*;
+    use crate::testutil::{ata, buy, jupiter_route, unsigned_tx, BONK, USDC};
+
+    /// Round-trips `tx` through its wire encoding, then resolves and inspects it
+    /// the way the engine does before signing.
+    fn check(wallet: &Pubkey, tx: &VersionedTransaction, tables: &[AddressLookupTableAccount]) -> Result<(), Violation> {
+        let tx: VersionedTransaction = bincode::deserialize(&bincode::serialize(tx).unwrap()).unwrap();
+        let loaded: Vec<Vec<Pubkey>> = tx
+            .message
+            .address_table_lookups()
+            .unwrap_or_default()
+            .iter()
+            .map(|l| tables.iter().find(|t| t.key == l.account_key).unwrap().addresses.clone())
+            .collect();
+        let accounts = resolve_accounts(&tx.message, &loaded)?;
+        TxFirewall::new(*wallet, &[USDC, BONK])?.inspect(&tx.message, &accounts)
+    }
+
+    #[test]
+    fn allows_a_jupiter_route_between_our_token_accounts() {
+        let wallet = Pubkey::new_unique();
+        let tx = unsigned_tx(
+            &wallet,
+            &[ComputeBudgetInstruction::set_compute_unit_limit(200_000), buy(&wallet, 5_000_000)],
+            &[],
+        );
+        check(&wallet, &tx, &[]).unwrap();
+    }
+
+    #[test]
+    fn rejects_an_unknown_program() {
+        let wallet = Pubkey::new_unique();
+        let drain = Instruction {
+            program_id: Pubkey::new_unique(),
+            accounts: vec![AccountMeta::new(ata(&wallet, USDC), false), AccountMeta::new_readonly(wallet, true)],
+            data: vec![1],
+        };
+        let tx = unsigned_tx(&wallet, &[buy(&wallet, 5_000_000), drain], &[]);
+        let v = check(&wallet, &tx, &[]).unwrap_err();
+        assert_eq!(v.instruction, Some(1));
+        assert!(v.reason.contains("not in allowlist"), "{v}");
+    }
+
+    #[test]
+    fn rejects_a_destination_owned_by_someone_else() {
+        let wallet = Pubkey::new_unique();
+        let thief = Pubkey::new_unique();
+        let ix = jupiter_route(&wallet, ata(&wallet, USDC), ata(&thief, BONK), 5_000_000);
+        let tx = unsigned_tx(&wallet, &[ix], &[]);
+        let v = check(&wallet, &tx, &[]).unwrap_err();
+        assert!(v.reason.contains("route destination"), "{v}");
+        assert!(v.reason.contains(&ata(&thief, BONK).to_string()), "{v}");
+    }
+
+    #[test]
+    fn rejects_a_destination_loaded_only_through_a_lookup_table() {
+        let wallet = Pubkey::new_unique();
+        let thief_ata = ata(&Pubkey::new_unique(), BONK);
+        let table = AddressLookupTableAccount {
+            key: Pubkey::new_unique(),
+            addresses: vec![Pubkey::new_unique(), thief_ata],
+        };
+        let ix = jupiter_route(&wallet, ata(&wallet, USDC), thief_ata, 5_000_000);
+        let tx = unsigned_tx(&wallet, &[ix], std::slice::from_ref(&table));
+        assert!(!tx.message.static_account_keys().contains(&thief_ata));
+
+        // An unresolved lookup is refused, not skipped.
+        assert!(resolve_accounts(&tx.message, &[]).is_err());
+
+        let v = check(&wallet, &tx, &[table]).unwrap_err();
+        assert!(v.reason.contains(&thief_ata.to_string()), "{v}");
+    }
+}
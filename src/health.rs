@@ -0,0 +1,188 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tracing::{info, warn};
+
+use crate::engine::Engine;
+
+/// How often the RPC endpoint is probed, and how long a probe may take.
+const RPC_PROBE_EVERY: Duration = Duration::from_secs(15);
+const RPC_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The loop whose staleness makes the daemon unhealthy: it enforces exits.
+pub const GATING_LOOP: &str = "positions";
+
+struct Ticks {
+    started: Instant,
+    loops: HashMap<&'static str, Instant>,
+    rpc: Option<RpcProbe>,
+}
+
+struct RpcProbe {
+    at: Instant,
+    error: Option<String>,
+}
+
+static TICKS: LazyLock<Mutex<Ticks>> = LazyLock::new(|| {
+    Mutex::new(Ticks {
+        started: Instant::now(),
+        loops: HashMap::new(),
+        rpc: None,
+    })
+});
+
+/// Starts the clock for loops that have not ticked yet. Call once at boot.
+pub fn init() {
+    LazyLock::force(&TICKS);
+}
+
+/// Records a completed iteration of `name`.
+pub fn tick(name: &'static str) {
+    TICKS.lock().expect("health lock").loops.insert(name, Instant::now());
+    crate::metrics::get()
+        .loop_last_tick
+        .with_label_values(&[name])
+        .set(chrono::Utc::now().timestamp());
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoopStatus {
+    /// Seconds since the last completed iteration (since boot if none yet).
+    pub age_secs: u64,
+    pub ticked: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RpcStatus {
+    pub ok: bool,
+    pub age_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    /// Positions loop fresh (liveness; what the watchdog checks).
+    pub live: bool,
+    /// Live and RPC reachable.
+    pub ready: bool,
+    pub loops: BTreeMap<&'static str, LoopStatus>,
+    pub rpc: RpcStatus,
+}
+
+/// Records progress inside a positions iteration, once per position, so a
+/// liquidation of several positions under the state lock is not taken for a
+/// hung loop. The staleness budget then covers one exit, not all.
+pub fn progress() {
+    tick(GATING_LOOP);
+}
+
+/// Current health against `max_tick_age`.
+pub fn report(max_tick_age: Duration) -> Report {
+    let t = TICKS.lock().expect("health lock");
+    let now = Instant::now();
+    let mut loops: BTreeMap<_, _> = t
+        .loops
+        .iter()
+        .map(|(name, at)| {
+            (
+                *name,
+                LoopStatus {
+                    age_secs: now.duration_since(*at).as_secs(),
+                    ticked: true,
+                },
+            )
+        })
+        .collect();
+
+    // Before the first tick, count from boot so startup isn't reported as stale.
+    let gating = loops.entry(GATING_LOOP).or_insert(LoopStatus {
+        age_secs: now.duration_since(t.started).as_secs(),
+        ticked: false,
+    });
+    let live = gating.age_secs <= max_tick_age.as_secs();
+
+    let rpc = match &t.rpc {
+        Some(p) => RpcStatus {
+            // An old result means the prober itself is stuck.
+            ok: p.error.is_none() && now.duration_since(p.at) <= RPC_PROBE_EVERY * 3,
+            age_secs: Some(now.duration_since(p.at).as_secs()),
+            error: p.error.clone(),
+        },
+        None => RpcStatus {
+            ok: false,
+            age_secs: None,
+            error: Some("not probed yet".into()),
+        },
+    };
+
+    Report {
+        live,
+        ready: live && rpc.ok,
+        loops,
+        rpc,
+    }
+}
+
+/// Probes the RPC endpoint (`getSlot`) every [`RPC_PROBE_EVERY`] for `/readyz`.
+pub async fn probe_rpc(engine: Engine) {
+    let url = engine.cfg.helius_http_url.clone();
+    let mut interval = tokio::time::interval(RPC_PROBE_EVERY);
+    let mut was_ok = true;
+    loop {
+        interval.tick().await;
+        let error = match tokio::time::timeout(RPC_PROBE_TIMEOUT, engine.rpc_slot()).await {
+            Ok(Ok(_)) => None,
+            // The RPC URL may carry an API key; never echo it.
+            Ok(Err(e)) => Some(e.to_string().replace(url.expose(), &url.summary())),
+            Err(_) => Some(format!("no answer within {}s", RPC_PROBE_TIMEOUT.as_secs())),
+        };
+        match (&error, was_ok) {
+            (Some(e), true) => warn!(error = %e, "health.rpc.unreachable"),
+            (None, false) => info!("health.rpc.recovered"),
+            _ => {}
+        }
+        was_ok = error.is_none();
+        TICKS.lock().expect("health lock").rpc = Some(RpcProbe {
+            at: Instant::now(),
+            error,
+        });
+    }
+}
+
+/// Tells systemd the daemon finished starting (`Type=notify`). No-op outside systemd.
+pub fn notify_ready() {
+    if let Err(e) = sd_notify::notify(false, &[sd_notify::NotifyState::Ready]) {
+        warn!(error = %e, "health.sd_notify_failed");
+    }
+}
+
+/// Pings the systemd watchdog at half of `WatchdogSec` while the daemon is live,
+/// so a stuck positions loop gets the service restarted.
+///
+/// `WATCHDOG_PID` is not checked: under `cargo run` the bot is not the main PID
+/// (the unit sets `NotifyAccess=all`).
+pub async fn watchdog(max_tick_age: Duration) {
+    let Some(usec) = std::env::var("WATCHDOG_USEC").ok().and_then(|v| v.parse::<u64>().ok()) else {
+        return;
+    };
+    let every = Duration::from_micros(usec / 2);
+    info!(every_ms = every.as_millis() as u64, "health.watchdog.enabled");
+
+    let mut interval = tokio::time::interval(every);
+    let mut was_live = true;
+    loop {
+        interval.tick().await;
+        let live = report(max_tick_age).live;
+        if live {
+            if let Err(e) = sd_notify::notify(false, &[sd_notify::NotifyState::Watchdog]) {
+                warn!(error = %e, "health.sd_notify_failed");
+            }
+        } else if was_live {
+            warn!(loop_name = GATING_LOOP, "health.watchdog.withheld: loop stale");
+        }
+        was_live = live;
+    }
+}
@@ -0,0 +1,362 @@
+mod template;
+
+use anyhow::{anyhow, Result};
+use std::sync::Arc;
+use std::{fs, fs::OpenOptions, io::Write, path::Path};
+
+use crate::config::Config;
+use crate::ledger::{LedgerEvent, TradeExit, TradeOpen};
+use crate::market::{MarketContext, TokenStats};
+use crate::notifier::events::{DailySummary, SummaryPeriod, TradeBrief};
+use crate::risk::ExitReason;
+use template::fill;
+
+pub use template::Template;
+
+/// SOL 24h move beyond which the market counts as trending.
+const TREND_PCT: f64 = 0.02;
+/// Realized slippage worth calling out.
+const HIGH_SLIPPAGE_BPS: f64 = 50.0;
+
+/// How the journal is written: template (language plus overrides) and the
+/// timezone timestamps are shown in.
+#[derive(Debug, Clone)]
+pub struct Format {
+    template: Arc<Template>,
+    tz: chrono_tz::Tz,
+}
+
+impl Format {
+    pub fn new(lang: &str, custom_template: Option<&str>, tz: &str) -> Result<Self> {
+        Ok(Self {
+            template: Arc::new(Template::load(lang, custom_template)?),
+            tz: tz.parse().map_err(|_| anyhow!("invalid tz: {tz}"))?,
+        })
+    }
+
+    pub fn from_config(cfg: &Config) -> Result<Self> {
+        Self::new(&cfg.journal_lang, cfg.journal_template_path.as_deref(), &cfg.tz)
+    }
+
+    /// Ledger timestamp (RFC 3339 UTC) in the configured timezone.
+    fn time(&self, ts: &str) -> String {
+        match chrono::DateTime::parse_from_rfc3339(ts) {
+            Ok(t) => t.with_timezone(&self.tz).format("%Y-%m-%d %H:%M:%S %:z").to_string(),
+            Err(_) => ts.to_string(),
+        }
+    }
+}
+
+fn append(trading_md_path: impl AsRef<Path>, text: &str) -> Result<()> {
+    let mut f = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(trading_md_path)?;
+    f.write_all(text.as_bytes())?;
+    Ok(())
+}
+
+/// Appends the Markdown rendering of one ledger event.
+pub fn append_event(trading_md_path: impl AsRef<Path>, event: &LedgerEvent, fmt: &Format) -> Result<()> {
+    append(trading_md_path, &render_event(event, fmt))
+}
+
+/// Appends a daily/weekly summary. Summaries are not in the ledger, so
+/// [`rebuild`] does not bring them back.
+pub fn append_summary(trading_md_path: impl AsRef<Path>, s: &DailySummary, fmt: &Format) -> Result<()> {
+    append(trading_md_path, &render_summary(s, fmt))
+}
+
+/// Rewrites the journal from the full ledger, keeping the file's header
+/// (everything up to the first `---` line).
+pub fn rebuild(trading_md_path: impl AsRef<Path>, events: &[LedgerEvent], fmt: &Format) -> Result<()> {
+    let path = trading_md_path.as_ref();
+    let existing = if path.exists() { fs::read_to_string(path)? } else { String::new() };
+    let header = match existing.find("\n---\n") {
+        Some(i) => existing[..i + 5].to_string(),
+        None => fmt.template.entry.header.clone(),
+    };
+
+    let mut out = header;
+    out.push('\n');
+    for e in events {
+        out.push_str(&render_event(e, fmt));
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, out)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+pub fn render_event(event: &LedgerEvent, fmt: &Format) -> String {
+    match event {
+        LedgerEvent::Open(o) => render_open(o, fmt),
+        LedgerEvent::Partial(x) => render_exit(x, &fmt.template.entry.partial, fmt),
+        LedgerEvent::Close(x) => render_exit(x, &fmt.template.entry.close, fmt),
+    }
+}
+
+fn or_dash(v: Option<&str>) -> String {
+    v.filter(|v| !v.is_empty()).unwrap_or("-").to_string()
+}
+
+fn render_open(o: &TradeOpen, fmt: &Format) -> String {
+    fill(
+        &fmt.template.entry.open,
+        &[
+            ("tz", fmt.tz.name().to_string()),
+            ("ts", fmt.time(&o.ts)),
+            ("position_id", o.position_id.clone()),
+            ("strategy", o.strategy.clone()),
+            ("base_mint", o.base_mint.clone()),
+            ("quote_mint", o.quote_mint.clone()),
+            ("tx", or_dash(o.tx.as_deref())),
+            ("size_usdc", format!("{:.2}", o.size_usdc)),
+            ("entry_price", format!("{:.6}", o.entry_price)),
+            ("stop_loss_pct", format!("{:.1}%", o.stop_loss_pct * 100.0)),
+            ("take_profit_pct", format!("{:.1}%", o.take_profit_pct * 100.0)),
+        ],
+    )
+}
+
+fn render_exit(x: &TradeExit, layout: &str, fmt: &Format) -> String {
+    let f = &x.fill;
+    let words = &fmt.template.words;
+    fill(
+        layout,
+        &[
+            ("tz", fmt.tz.name().to_string()),
+            ("ts", fmt.time(&x.ts)),
+            ("position_id", x.position_id.clone()),
+            ("strategy", x.strategy.clone()),
+            ("base_mint", x.base_mint.clone()),
+            ("quote_mint", x.quote_mint.clone()),
+            ("buy_tx", or_dash(x.buy_tx.as_deref())),
+            ("sell_tx", or_dash(x.sell_tx.as_deref())),
+            ("size_usdc", format!("{:.2}", x.size_usdc)),
+            ("pnl_usdc", format!("{:.2}", x.pnl_usdc)),
+            ("pnl_pct", format!("{:.2}%", x.pnl_pct * 100.0)),
+            ("reason", x.reason.to_string()),
+            ("exit_price", format!("{:.6}", x.exit_price)),
+            ("mark_price", format!("{:.6}", x.mark_price)),
+            (
+                "received",
+                f.out_amount.map_or(words.unconfirmed.clone(), |out| format!("{out:.6}")),
+            ),
+            ("quoted", format!("{:.6}", f.quoted_out_amount)),
+            ("slippage", f.slippage_bps().map_or("-".into(), |b| format!("{b:.1} bps"))),
+            ("fee", f.fee_lamports.map_or("-".into(), |l| format!("{l} lamports"))),
+            ("hold", x.hold_secs.map_or("-".into(), |secs| format!("{}m", secs / 60))),
+            ("lesson", lesson(x, fmt)),
+            ("sentiment", sentiment(x.market.as_ref(), fmt)),
+        ],
+    )
+}
+
+fn pct(v: f64) -> String {
+    format!("{:+.1}%", v * 100.0)
+}
+
+fn opt_pct(v: Option<f64>) -> String {
+    v.map_or("-".into(), pct)
+}
+
+fn usd_short(v: f64) -> String {
+    match v.abs() {
+        a if a >= 1e6 => format!("${:.1}M", v / 1e6),
+        a if a >= 1e3 => format!("${:.0}k", v / 1e3),
+        _ => format!("${v:.0}"),
+    }
+}
+
+/// Volume and price action of SOL and the token at close.
+fn sentiment(market: Option<&MarketContext>, fmt: &Format) -> String {
+    let t = &fmt.template.sentiment;
+    let Some(m) = market else {
+        return t.no_data.clone();
+    };
+    let mut lines = vec![];
+
+    if let Some(sol) = &m.sol {
+        match sol.price_change_24h_pct {
+            Some(h24) => {
+                let trend = if h24 >= TREND_PCT {
+                    &t.trend_up
+                } else if h24 <= -TREND_PCT {
+                    &t.trend_down
+                } else {
+                    &t.trend_flat
+                };
+                lines.push(fill(
+                    &t.sol,
+                    &[
+                        ("trend", trend.clone()),
+                        ("change_24h", pct(h24)),
+                        ("change_1h", opt_pct(sol.price_change_1h_pct)),
+                    ],
+                ));
+            }
+            None => lines.push(t.sol_no_data.clone()),
+        }
+    }
+
+    if let Some(token) = &m.token {
+        let mut parts = vec![];
+        if token.price_change_1h_pct.is_some() || token.price_change_24h_pct.is_some() {
+            parts.push(fill(
+                &t.token_price,
+                &[
+                    ("change_1h", opt_pct(token.price_change_1h_pct)),
+                    ("change_24h", opt_pct(token.price_change_24h_pct)),
+                ],
+            ));
+        }
+        if let (Some(v1), Some(v24)) = (token.volume_1h_usd, token.volume_24h_usd) {
+            parts.push(fill(
+                &t.token_volume,
+                &[("volume_1h", usd_short(v1)), ("volume_24h", usd_short(v24))],
+            ));
+        }
+        if let Some(l) = token.liquidity_usd {
+            parts.push(fill(&t.token_liquidity, &[("liquidity", usd_short(l))]));
+        }
+        let details = if parts.is_empty() { t.token_no_data.clone() } else { parts.join(", ") };
+        lines.push(fill(&t.token, &[("details", details)]));
+
+        if let Some(r) = token.volume_1h_vs_avg() {
+            let read = if r >= 1.5 {
+                &t.volume_high
+            } else if r <= 0.5 {
+                &t.volume_low
+            } else {
+                &t.volume_normal
+            };
+            lines.push(fill(&t.volume, &[("ratio", format!("{r:.1}")), ("read", read.clone())]));
+        }
+    }
+
+    let read = overall(m.sol.as_ref(), m.token.as_ref(), fmt);
+    lines.push(fill(&t.overall, &[("read", read.to_string())]));
+    lines.join("\n")
+}
+
+fn overall<'a>(sol: Option<&TokenStats>, token: Option<&TokenStats>, fmt: &'a Format) -> &'a str {
+    let t = &fmt.template.sentiment;
+    let sol = sol.and_then(|s| s.price_change_24h_pct).unwrap_or(0.0);
+    let token = token.and_then(|t| t.price_change_1h_pct).unwrap_or(0.0);
+    match (sol >= TREND_PCT, sol <= -TREND_PCT, token > 0.0) {
+        (true, _, true) => &t.favorable,
+        (_, true, false) => &t.adverse,
+        _ => &t.mixed,
+    }
+}
+
+/// What the trade says about the entry signal and the execution, from the
+/// ledger facts.
+fn lesson(x: &TradeExit, fmt: &Format) -> String {
+    let t = &fmt.template.lesson;
+    let mut lines = vec![];
+
+    lines.push(match x.reason {
+        ExitReason::TakeProfit => t.take_profit.clone(),
+        ExitReason::StopLoss => t.stop_loss.clone(),
+        ExitReason::TrailingStop => t.trailing_stop.clone(),
+        ExitReason::DailyLossLimit | ExitReason::HardStop => t.risk_limit.clone(),
+        ExitReason::Manual => t.manual.clone(),
+        ExitReason::Other => t.other.clone(),
+    });
+
+    if x.entry_price > 0.0 && x.peak_price > 0.0 {
+        let run_up = (x.peak_price - x.entry_price) / x.entry_price;
+        if run_up <= 0.0 {
+            lines.push(t.never_in_profit.clone());
+        } else {
+            let given_back = run_up - x.pnl_pct;
+            let given_back = if given_back > 0.0 {
+                fill(&t.given_back, &[("points", format!("{:.1}", given_back * 100.0))])
+            } else {
+                String::new()
+            };
+            lines.push(fill(
+                &t.run_up,
+                &[("run_up", pct(run_up)), ("pnl_pct", pct(x.pnl_pct)), ("given_back", given_back)],
+            ));
+        }
+    }
+
+    lines.push(match x.fill.slippage_bps() {
+        Some(bps) if bps > HIGH_SLIPPAGE_BPS => fill(&t.slippage_high, &[("bps", format!("{bps:.0}"))]),
+        Some(bps) => fill(&t.slippage, &[("bps", format!("{bps:.0}"))]),
+        None => t.slippage_unknown.clone(),
+    });
+
+    if let Some(secs) = x.hold_secs {
+        let minutes = secs / 60;
+        let read = match (x.reason, minutes) {
+            (ExitReason::StopLoss, m) if m < 5 => Some(&t.hold_quick),
+            (_, m) if m >= 24 * 60 => Some(&t.hold_long),
+            _ => None,
+        };
+        if let Some(read) = read {
+            lines.push(fill(read, &[("minutes", minutes.to_string())]));
+        }
+    }
+
+    if let Some(h1) = x.market.as_ref().and_then(|m| m.token.as_ref()).and_then(|t| t.price_change_1h_pct) {
+        if x.pnl_usdc < 0.0 && h1 < 0.0 {
+            lines.push(fill(&t.token_falling, &[("change", pct(h1))]));
+        }
+    }
+
+    if !x.note.is_empty() {
+        lines.push(fill(&t.note, &[("note", x.note.clone())]));
+    }
+    lines.join("\n")
+}
+
+fn render_summary(d: &DailySummary, fmt: &Format) -> String {
+    let words = &fmt.template.words;
+    let title = match d.period {
+        SummaryPeriod::Day => &words.day_summary,
+        SummaryPeriod::Week => &words.week_summary,
+    };
+    let brief = |t: &Option<TradeBrief>| match t {
+        Some(t) => format!("{} {:.2} USDC ({:.2}%, {})", t.base_mint, t.pnl_usdc, t.pnl_pct * 100.0, t.reason),
+        None => "-".into(),
+    };
+    let equity = match (d.start_equity_usdc, d.end_equity_usdc) {
+        (Some(a), Some(b)) => format!("{a:.2} -> {b:.2} ({:+.2})", b - a),
+        (_, Some(b)) => format!("{b:.2}"),
+        _ => "-".into(),
+    };
+    let modes = if d.mode_changes.is_empty() {
+        words.no_mode_changes.clone()
+    } else {
+        d.mode_changes
+            .iter()
+            .map(|m| format!("{:?} -> {:?} ({}: {})", m.from, m.to, m.by, m.reason))
+            .collect::<Vec<_>>()
+            .join("; ")
+    };
+
+    fill(
+        &fmt.template.entry.summary,
+        &[
+            ("title", title.clone()),
+            ("key", d.day_key.clone()),
+            ("trades", d.trades.to_string()),
+            ("wins", d.wins.to_string()),
+            ("realized_pnl", format!("{:.2}", d.realized_pnl_usdc + 0.0)), // no "-0.00"
+            ("unrealized_pnl", format!("{:.2}", d.unrealized_pnl_usdc + 0.0)),
+            ("equity", equity),
+            ("fees", format!("{} lamports", d.fees_lamports)),
+            ("best", brief(&d.best)),
+            ("worst", brief(&d.worst)),
+            ("mode_changes", modes),
+            ("alerts", d.alerts.to_string()),
+            ("open_positions", d.open_positions.to_string()),
+            ("mode", format!("{:?}", d.mode)),
+        ],
+    )
+}
@@ -0,0 +1,143 @@
+use std::fs;
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+const ES: &str = include_str!("templates/es.toml");
+const EN: &str = include_str!("templates/en.toml");
+
+/// Bundled template languages.
+pub const LANGS: &[&str] = &["es", "en"];
+
+/// Journal wording: entry layouts plus the phrases lesson and sentiment are
+/// built from. See `templates/es.toml` for every key and its placeholders.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Template {
+    pub entry: Entries,
+    pub words: Words,
+    pub lesson: Lesson,
+    pub sentiment: Sentiment,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Entries {
+    pub header: String,
+    pub open: String,
+    pub close: String,
+    pub partial: String,
+    pub summary: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Words {
+    pub unconfirmed: String,
+    pub day_summary: String,
+    pub week_summary: String,
+    pub no_mode_changes: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Lesson {
+    pub take_profit: String,
+    pub stop_loss: String,
+    pub trailing_stop: String,
+    pub risk_limit: String,
+    pub manual: String,
+    pub other: String,
+    pub never_in_profit: String,
+    pub run_up: String,
+    pub given_back: String,
+    pub slippage_high: String,
+    pub slippage: String,
+    pub slippage_unknown: String,
+    pub hold_quick: String,
+    pub hold_long: String,
+    pub token_falling: String,
+    pub note: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Sentiment {
+    pub no_data: String,
+    pub sol: String,
+    pub sol_no_data: String,
+    pub trend_up: String,
+    pub trend_down: String,
+    pub trend_flat: String,
+    pub token: String,
+    pub token_price: String,
+    pub token_volume: String,
+    pub token_liquidity: String,
+    pub token_no_data: String,
+    pub volume: String,
+    pub volume_high: String,
+    pub volume_low: String,
+    pub volume_normal: String,
+    pub overall: String,
+    pub favorable: String,
+    pub adverse: String,
+    pub mixed: String,
+}
+
+impl Template {
+    /// Bundled template for `lang`, with the keys of `custom_path` (a TOML
+    /// file of the same shape) laid over it.
+    pub fn load(lang: &str, custom_path: Option<&str>) -> Result<Self> {
+        let bundled = match lang {
+            "es" => ES,
+            "en" => EN,
+            other => return Err(anyhow!("unknown journal language '{other}' (expected one of {LANGS:?})")),
+        };
+        let mut table: toml::Table = bundled.parse().expect("bundled journal template is valid TOML");
+
+        if let Some(path) = custom_path {
+            let raw = fs::read_to_string(path).map_err(|e| anyhow!("journal template {path}: {e}"))?;
+            let custom: toml::Table = raw.parse().map_err(|e| anyhow!("journal template {path}: {e}"))?;
+            overlay(&mut table, custom, path)?;
+        }
+
+        toml::Value::Table(table)
+            .try_into()
+            .map_err(|e| anyhow!("journal template: {e}"))
+    }
+}
+
+/// Replaces the keys of `base` found in `custom`, section by section.
+/// Unknown keys are errors so a typo doesn't go unnoticed.
+fn overlay(base: &mut toml::Table, custom: toml::Table, path: &str) -> Result<()> {
+    for (key, value) in custom {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(b)), toml::Value::Table(c)) => overlay(b, c, path)?,
+            (Some(slot @ toml::Value::String(_)), v @ toml::Value::String(_)) => *slot = v,
+            (Some(_), _) => return Err(anyhow!("journal template {path}: '{key}' has the wrong type")),
+            (None, _) => return Err(anyhow!("journal template {path}: unknown key '{key}'")),
+        }
+    }
+    Ok(())
+}
+
+/// Fills `{name}` placeholders in one pass (values are not scanned again);
+/// unknown ones are left as they are.
+pub fn fill(template: &str, values: &[(&str, String)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let known = after
+            .find('}')
+            .and_then(|end| values.iter().find(|(name, _)| *name == &after[..end]).map(|(_, v)| (end, v)));
+        match known {
+            Some((end, value)) => {
+                out.push_str(value);
+                rest = &after[end + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
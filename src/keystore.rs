@@ -0,0 +1,296 @@
+use std::io::Write;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Context, Result};
+use base64::Engine as _;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use tracing::info;
+use zeroize::Zeroizing;
+
+use crate::config::Config;
+
+const VERSION: u32 = 1;
+const CIPHER: &str = "xchacha20poly1305";
+const KDF: &str = "scrypt";
+
+/// scrypt cost for new keystores (N = 2^15, r = 8, p = 1: ~32 MiB, well under a second).
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+/// Env var holding the passphrase directly (prefer a passphrase file).
+pub const PASSPHRASE_ENV: &str = "SIE_KEYSTORE_PASSPHRASE";
+
+/// `SIE_KEYSTORE_PASSPHRASE`, moved out of the environment by [`take_env_passphrase`].
+static ENV_PASSPHRASE: Mutex<Option<Zeroizing<String>>> = Mutex::new(None);
+
+/// Moves `SIE_KEYSTORE_PASSPHRASE` out of the process environment, so child
+/// processes and env dumps never see it. Call from `main` before the tokio
+/// runtime starts: `remove_var` is only sound while the process is single-threaded.
+pub fn take_env_passphrase() {
+    let Ok(value) = std::env::var(PASSPHRASE_ENV) else {
+        return;
+    };
+    std::env::remove_var(PASSPHRASE_ENV);
+    *ENV_PASSPHRASE.lock().expect("passphrase lock") = Some(Zeroizing::new(value));
+}
+
+/// Whether a passphrase came in through `SIE_KEYSTORE_PASSPHRASE`.
+pub fn has_env_passphrase() -> bool {
+    ENV_PASSPHRASE.lock().expect("passphrase lock").is_some()
+}
+
+/// On-disk encrypted keypair. The 64-byte Solana keypair is sealed with
+/// XChaCha20-Poly1305 under a scrypt-derived key; the pubkey is bound as AAD.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KeystoreFile {
+    pub version: u32,
+    pub pubkey: String,
+    pub kdf: KdfParams,
+    pub cipher: String,
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct KdfParams {
+    pub name: String,
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+    pub salt: String,
+}
+
+/// The trading keypair, unlocked once at startup. The secret bytes are wiped
+/// on drop; each signer built from them is short-lived.
+pub struct UnlockedKeypair {
+    bytes: Zeroizing<Vec<u8>>,
+    pubkey: Pubkey,
+}
+
+impl UnlockedKeypair {
+    pub(crate) fn from_bytes(bytes: Zeroizing<Vec<u8>>) -> Result<Self> {
+        let pubkey = Keypair::try_from(&bytes[..])
+            .map_err(|e| anyhow!("invalid keypair bytes: {e}"))?
+            .pubkey();
+        Ok(Self { bytes, pubkey })
+    }
+
+    pub fn pubkey(&self) -> Pubkey {
+        self.pubkey
+    }
+
+    /// A signer for one operation; drop it as soon as the tx is signed.
+    pub fn keypair(&self) -> Result<Keypair> {
+        Keypair::try_from(&self.bytes[..]).map_err(|e| anyhow!("invalid keypair bytes: {e}"))
+    }
+}
+
+fn b64(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+fn unb64(field: &str, s: &str) -> Result<Vec<u8>> {
+    base64::engine::general_purpose::STANDARD
+        .decode(s)
+        .map_err(|e| anyhow!("keystore {field}: {e}"))
+}
+
+fn derive_key(passphrase: &[u8], salt: &[u8], log_n: u8, r: u32, p: u32) -> Result<Zeroizing<[u8; 32]>> {
+    let params = scrypt::Params::new(log_n, r, p, 32).map_err(|e| anyhow!("scrypt params: {e}"))?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    scrypt::scrypt(passphrase, salt, &params, key.as_mut()).map_err(|e| anyhow!("scrypt: {e}"))?;
+    Ok(key)
+}
+
+impl KeystoreFile {
+    pub fn encrypt(kp: &Keypair, passphrase: &[u8]) -> Result<Self> {
+        if passphrase.is_empty() {
+            return Err(anyhow!("empty passphrase"));
+        }
+
+        let mut salt = [0u8; 16];
+        let mut nonce = [0u8; 24];
+        rand::thread_rng().fill_bytes(&mut salt);
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let pubkey = kp.pubkey().to_string();
+        let key = derive_key(passphrase, &salt, SCRYPT_LOG_N, SCRYPT_R, SCRYPT_P)?;
+        let plaintext = Zeroizing::new(kp.to_bytes().to_vec());
+        let ciphertext = XChaCha20Poly1305::new(key.as_ref().into())
+            .encrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &plaintext,
+                    aad: pubkey.as_bytes(),
+                },
+            )
+            .map_err(|_| anyhow!("keystore encryption failed"))?;
+
+        Ok(Self {
+            version: VERSION,
+            pubkey,
+            kdf: KdfParams {
+                name: KDF.to_string(),
+                log_n: SCRYPT_LOG_N,
+                r: SCRYPT_R,
+                p: SCRYPT_P,
+                salt: b64(&salt),
+            },
+            cipher: CIPHER.to_string(),
+            nonce: b64(&nonce),
+            ciphertext: b64(&ciphertext),
+        })
+    }
+
+    pub fn decrypt(&self, passphrase: &[u8]) -> Result<UnlockedKeypair> {
+        if self.version != VERSION || self.kdf.name != KDF || self.cipher != CIPHER {
+            return Err(anyhow!(
+                "unsupported keystore (version {}, kdf {}, cipher {})",
+                self.version,
+                self.kdf.name,
+                self.cipher
+            ));
+        }
+
+        // The cost comes from the file: never let it ask for more than we write.
+        if self.kdf.log_n > SCRYPT_LOG_N || self.kdf.r > SCRYPT_R || self.kdf.p > SCRYPT_P {
+            return Err(anyhow!(
+                "keystore scrypt cost too high (log_n {}, r {}, p {}; max {SCRYPT_LOG_N}, {SCRYPT_R}, {SCRYPT_P})",
+                self.kdf.log_n,
+                self.kdf.r,
+                self.kdf.p
+            ));
+        }
+
+        let salt = unb64("salt", &self.kdf.salt)?;
+        let nonce = unb64("nonce", &self.nonce)?;
+        if nonce.len() != 24 {
+            return Err(anyhow!("keystore nonce: expected 24 bytes"));
+        }
+        let ciphertext = unb64("ciphertext", &self.ciphertext)?;
+
+        let key = derive_key(passphrase, &salt, self.kdf.log_n, self.kdf.r, self.kdf.p)?;
+        let plaintext = XChaCha20Poly1305::new(key.as_ref().into())
+            .decrypt(
+                XNonce::from_slice(&nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: self.pubkey.as_bytes(),
+                },
+            )
+            .map_err(|_| anyhow!("wrong passphrase or corrupted keystore"))?;
+
+        let unlocked = UnlockedKeypair::from_bytes(Zeroizing::new(plaintext))?;
+        if unlocked.pubkey().to_string() != self.pubkey {
+            return Err(anyhow!("keystore pubkey does not match the decrypted keypair"));
+        }
+        Ok(unlocked)
+    }
+
+    pub fn read(path: &str) -> Result<Self> {
+        let raw = std::fs::read_to_string(path).context("reading keystore file")?;
+        serde_json::from_str(&raw).context("parsing keystore file")
+    }
+
+    pub fn write(&self, path: &str) -> Result<()> {
+        write_private(path, serde_json::to_string_pretty(self)?.as_bytes())
+    }
+}
+
+/// Creates `path` with mode 0600; refuses to overwrite an existing file.
+pub fn write_private(path: &str, contents: &[u8]) -> Result<()> {
+    let mut opts = std::fs::OpenOptions::new();
+    opts.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        opts.mode(0o600);
+    }
+    let mut f = opts.open(path).with_context(|| format!("creating {path}"))?;
+    f.write_all(contents)?;
+    f.sync_all()?;
+    Ok(())
+}
+
+/// Reads a plaintext Solana CLI keypair file (JSON array of 64 bytes).
+/// Errors leave the path out (see [`crate::secret::Secret`]).
+pub fn read_plain_keypair(path: &str) -> Result<UnlockedKeypair> {
+    let raw = Zeroizing::new(std::fs::read_to_string(path).context("reading keypair file")?);
+    let bytes: Vec<u8> = serde_json::from_str(&raw).context("parsing keypair file")?;
+    UnlockedKeypair::from_bytes(Zeroizing::new(bytes))
+}
+
+/// Passphrase from a file (first line) or, failing that, `SIE_KEYSTORE_PASSPHRASE`
+/// (as taken by [`take_env_passphrase`]).
+pub fn passphrase_from(file: Option<&str>) -> Result<Option<Zeroizing<String>>> {
+    if let Some(path) = file {
+        let raw = Zeroizing::new(
+            std::fs::read_to_string(path).context("reading passphrase file")?,
+        );
+        let line = raw.lines().next().unwrap_or_default();
+        return Ok(Some(Zeroizing::new(line.to_string())));
+    }
+    Ok(ENV_PASSPHRASE.lock().expect("passphrase lock").clone())
+}
+
+/// Unlocks the trading keypair once at startup: the encrypted keystore if
+/// configured, else the plaintext `SOL_KEYPAIR_PATH`, else none (dry-run).
+pub fn unlock_from_config(cfg: &Config) -> Result<Option<UnlockedKeypair>> {
+    if let Some(path) = &cfg.keystore_path {
+        let file = KeystoreFile::read(path.expose())?;
+        let passphrase = passphrase_from(cfg.keystore_passphrase_file.as_ref().map(|p| p.expose()))?
+            .ok_or_else(|| {
+                anyhow!("keystore configured but no passphrase (SIE_KEYSTORE_PASSPHRASE_FILE or {PASSPHRASE_ENV})")
+            })?;
+        let kp = file.decrypt(passphrase.as_bytes())?;
+        info!(pubkey = %kp.pubkey(), "keystore.unlocked");
+        return Ok(Some(kp));
+    }
+
+    if let Some(path) = &cfg.sol_keypair_path {
+        let kp = read_plain_keypair(path.expose())?;
+        info!(pubkey = %kp.pubkey(), "keypair.loaded_plaintext");
+        return Ok(Some(kp));
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decrypt_round_trips() {
+        let kp = Keypair::new();
+        let file = KeystoreFile::encrypt(&kp, b"hunter2").unwrap();
+        assert_eq!(file.decrypt(b"hunter2").unwrap().pubkey(), kp.pubkey());
+        assert!(file.decrypt(b"hunter3").is_err());
+    }
+
+    #[test]
+    fn decrypt_rejects_scrypt_cost_above_what_we_write() {
+        let kp = Keypair::new();
+        let too_high = [
+            (SCRYPT_LOG_N + 1, SCRYPT_R, SCRYPT_P),
+            (SCRYPT_LOG_N, 1 << 20, SCRYPT_P),
+            (SCRYPT_LOG_N, SCRYPT_R, 64),
+        ];
+        for (log_n, r, p) in too_high {
+            let mut file = KeystoreFile::encrypt(&kp, b"hunter2").unwrap();
+            file.kdf.log_n = log_n;
+            file.kdf.r = r;
+            file.kdf.p = p;
+            match file.decrypt(b"hunter2") {
+                Ok(_) => panic!("accepted log_n {log_n}, r {r}, p {p}"),
+                Err(e) => assert!(e.to_string().contains("scrypt cost too high"), "{e}"),
+            }
+        }
+    }
+}
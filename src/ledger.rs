@@ -0,0 +1,147 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::engine::Fill;
+use crate::market::MarketContext;
+use crate::risk::ExitReason;
+use crate::state::Position;
+
+/// One line of the trade ledger (JSONL). The ledger is the record of truth;
+/// the Markdown journal is rendered from it.
+///
+/// Only `Close` is written for now. `Open` and `Partial` stay in the schema so
+/// readers already handle them when an entry or scale-out path lands.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum LedgerEvent {
+    Open(TradeOpen),
+    /// Part of a position sold; the rest stays open.
+    Partial(TradeExit),
+    Close(TradeExit),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeOpen {
+    /// RFC 3339 UTC.
+    pub ts: String,
+    pub position_id: String,
+    pub strategy: String,
+    pub base_mint: String,
+    pub quote_mint: String,
+    pub size_usdc: f64,
+    /// Base units bought.
+    pub base_amount: u64,
+    pub entry_price: f64,
+    pub stop_loss_pct: f64,
+    pub take_profit_pct: f64,
+    pub tx: Option<String>,
+    pub fill: Option<Fill>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeExit {
+    /// RFC 3339 UTC.
+    pub ts: String,
+    pub position_id: String,
+    pub strategy: String,
+    pub base_mint: String,
+    pub quote_mint: String,
+    /// When the position was opened (RFC 3339), if known.
+    pub opened_at: Option<String>,
+    pub hold_secs: Option<i64>,
+    /// Base units sold.
+    pub base_amount: u64,
+    /// Cost basis of the part sold (USDC).
+    pub size_usdc: f64,
+    pub entry_price: f64,
+    /// Stop distance at entry; with `size_usdc` it defines 1R.
+    #[serde(default)]
+    pub stop_loss_pct: f64,
+    /// Highest price seen while open (how far the entry signal ran).
+    #[serde(default)]
+    pub peak_price: f64,
+    /// Price that triggered the exit.
+    pub mark_price: f64,
+    /// Price actually obtained (quote per base, from the fill).
+    pub exit_price: f64,
+    pub pnl_usdc: f64,
+    pub pnl_pct: f64,
+    pub reason: ExitReason,
+    pub note: String,
+    pub buy_tx: Option<String>,
+    pub sell_tx: Option<String>,
+    pub fill: Fill,
+    /// SOL and token market stats at close, if they could be fetched.
+    #[serde(default)]
+    pub market: Option<MarketContext>,
+}
+
+impl TradeExit {
+    /// Builds the exit record of `p` from the sell fill. PnL is what the sell
+    /// returned (quote units) against the cost basis.
+    pub fn new(p: &Position, strategy: &str, reason: ExitReason, note: &str, mark_price: f64, fill: Fill) -> Self {
+        let now = chrono::Utc::now();
+        let hold_secs = p
+            .opened_at
+            .as_deref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| (now - t.with_timezone(&chrono::Utc)).num_seconds());
+        let exit_usdc = fill.out_or_quoted();
+        let pnl_usdc = exit_usdc - p.size_usdc;
+        Self {
+            ts: now.to_rfc3339(),
+            position_id: p.id.clone(),
+            strategy: strategy.to_string(),
+            base_mint: p.base_mint.clone(),
+            quote_mint: p.quote_mint.clone(),
+            opened_at: p.opened_at.clone(),
+            hold_secs,
+            base_amount: p.base_amount,
+            size_usdc: p.size_usdc,
+            entry_price: p.entry_price,
+            stop_loss_pct: p.stop_loss_pct,
+            peak_price: p.peak_price,
+            mark_price,
+            exit_price: fill.price().unwrap_or(mark_price),
+            pnl_usdc,
+            pnl_pct: if p.size_usdc > 0.0 { pnl_usdc / p.size_usdc } else { 0.0 },
+            reason,
+            note: note.to_string(),
+            buy_tx: p.buy_tx.clone(),
+            sell_tx: p.sell_tx.clone(),
+            fill,
+            market: None,
+        }
+    }
+}
+
+impl LedgerEvent {
+    /// The exit record for partials and closes.
+    pub fn exit(&self) -> Option<&TradeExit> {
+        match self {
+            Self::Open(_) => None,
+            Self::Partial(x) | Self::Close(x) => Some(x),
+        }
+    }
+}
+
+pub fn append(path: impl AsRef<Path>, event: &LedgerEvent) -> Result<()> {
+    crate::logger::append_line(path, &serde_json::to_string(event)?)
+}
+
+/// All events, oldest first. Missing file => no events.
+pub fn read(path: impl AsRef<Path>) -> Result<Vec<LedgerEvent>> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let raw = fs::read_to_string(path)?;
+    raw.lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(i, l)| serde_json::from_str(l).map_err(|e| anyhow!("{}:{}: {e}", path.display(), i + 1)))
+        .collect()
+}
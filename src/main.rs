@@ -1,35 +1,112 @@
+mod balance;
+mod chatops;
+mod cli;
 mod config;
+mod control;
 mod engine;
+mod exits;
+mod firewall;
+mod health;
 mod journal;
 mod jupiter;
+mod keystore;
+mod ledger;
 mod logger;
+mod market;
+mod metrics;
 mod monitoring;
 mod notifier;
+mod reload;
+mod report;
 mod risk;
+mod signer;
+mod secret;
 mod state;
 mod time;
+mod validation;
 mod strategy;
+mod summary;
+#[cfg(test)]
+mod testutil;
 
 use anyhow::Result;
+use clap::Parser;
 use rand::{thread_rng, Rng};
-use tracing::{error, info, warn};
+use tracing::{error, info, info_span, warn, Instrument};
 
+use crate::cli::{Cli, Command, ConfigAction, ModeAction};
 use crate::config::Config;
 use crate::engine::Engine;
-use crate::notifier::Notifier;
-use crate::risk::{BotMode, RiskEvent, RiskParams, RiskState};
+use crate::exits::CloseOutcome;
+use crate::notifier::events::ModeChanged;
+use crate::notifier::{Event, Notifier, Severity};
+use crate::reload::LiveParams;
+use crate::risk::{BotMode, RiskEvent, RiskState};
 use crate::state::{PersistedState, StateStore};
+use crate::summary::SummaryOptions;
 
-#[tokio::main]
-async fn main() -> Result<()> {
+fn main() -> Result<()> {
     let _ = dotenvy::dotenv();
-    monitoring::init_tracing();
+    // Still single-threaded: the only point where touching the env is sound.
+    crate::keystore::take_env_passphrase();
+
+    let cli = Cli::parse();
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(run(cli))
+}
 
-    let cfg = Config::from_env()?;
-    info!(?cfg, "boot");
+async fn run(cli: Cli) -> Result<()> {
+    let json = cli.json;
+    match cli.command.unwrap_or(Command::Run) {
+        Command::Run => run_daemon().await,
+        Command::Status => crate::cli::status(json),
+        Command::Positions => crate::cli::positions(json),
+        Command::History { limit, modes } => crate::cli::history(json, limit, modes),
+        Command::Report {
+            since,
+            strategy,
+            format,
+            out,
+        } => crate::cli::report(json, since, strategy, format, out),
+        Command::Journal { action } => crate::cli::journal(json, action),
+        Command::Config { action: ConfigAction::Check } => crate::cli::config_check(json),
+        Command::Keystore { action } => crate::cli::keystore(json, action),
+        Command::Signer { action } => {
+            monitoring::init_tracing();
+            crate::cli::signer(action).await
+        }
+        Command::Mode { api, action } => crate::cli::mode(json, api, action).await,
+        Command::Resume { api, reason } => crate::cli::mode(json, api, ModeAction::Resume { reason }).await,
+        Command::Close { api, id, reason, .. } => crate::cli::close(json, api, id, reason).await,
+    }
+}
 
-    let notifier = Notifier::new(cfg.slack_webhook_url.clone());
-    let engine = Engine::new(cfg.clone());
+async fn run_daemon() -> Result<()> {
+    crate::health::init();
+    // Before the slow boot steps: SIGHUP's default action would kill the daemon.
+    // A reload sent meanwhile is kept and applied once the watcher runs.
+    let hup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+
+    let (cfg, report) = Config::load_with_report()?;
+    // After the config: it says where spans are exported.
+    monitoring::init_daemon_tracing(cfg.otlp_endpoint.as_deref())?;
+    if report.has_errors() {
+        return Err(anyhow::anyhow!("{report}"));
+    }
+    for w in report.warnings() {
+        warn!(key = %w.key, "config.warning: {}", w.message);
+    }
+    cfg.log_summary();
+
+    let notifier = Notifier::from_config(&cfg);
+    // Delivery worker: retries from the on-disk outbox, including alerts left by a previous run.
+    notifier.start()?;
+    info!(sinks = ?notifier.sink_names(), "notifier.ready");
+    // Set up the signer once (unlocks the keystore / connects to the remote signer).
+    let signer = crate::signer::from_config(&cfg).await?;
+    let engine = Engine::new(cfg.clone(), signer);
 
     // Load or initialize state.json
     let store = StateStore::new(&cfg.state_path);
@@ -46,19 +123,12 @@ async fn main() -> Result<()> {
         }
     };
 
-    // Risk params from config
-    let risk_params = RiskParams {
-        capital_usdc: cfg.capital_usdc,
-        position_size_usdc: cfg.position_size_usdc,
-        max_open_positions: cfg.max_open_positions,
-        max_daily_loss_pct: cfg.max_daily_loss_pct,
-        stop_loss_pct: cfg.stop_loss_pct,
-        take_profit_pct: cfg.take_profit_pct,
-        trailing_arm_pct: cfg.trailing_arm_pct,
-        portfolio_hard_stop_pct: cfg.portfolio_hard_stop_pct,
-    };
+    // Risk / strategy params from config; re-published on reload (SIGHUP or file change)
+    let (params_tx, params_rx) = tokio::sync::watch::channel(LiveParams::from_config(&cfg));
+
+    let max_tick_age = std::time::Duration::from_secs(cfg.health_max_tick_age_secs);
 
-    // Heartbeat log every 5m
+    // Heartbeat log every 5m, only while the positions loop is alive
     {
         let hb_path = cfg.heartbeat_log_path.clone();
         let notifier_hb = notifier.clone();
@@ -66,261 +136,359 @@ async fn main() -> Result<()> {
             let mut interval = tokio::time::interval(std::time::Duration::from_secs(300));
             loop {
                 interval.tick().await;
+                if !crate::health::report(max_tick_age).live {
+                    warn!("heartbeat.skipped: positions loop stale");
+                    continue;
+                }
                 if let Err(e) = crate::logger::append_line(&hb_path, &crate::logger::heartbeat_line()) {
                     error!(error = %e, "heartbeat.log.write_failed");
-                    let _ = notifier_hb.alert(&format!("[SIE] heartbeat log write failed: {e}")).await;
+                    notifier_hb
+                        .alert_keyed(Severity::Warning, "heartbeat.write", &format!("[SIE] heartbeat log write failed: {e}"));
                 }
             }
         });
     }
 
-    // Positions loop every 5s
-    {
-        let notifier_pos = notifier.clone();
-        let engine_pos = engine.clone();
+    // Balance oracle: mark wallet equity and drive daily loss / hard stop from it
+    if cfg.has_signing_key() {
+        let notifier_eq = notifier.clone();
+        let oracle = crate::balance::BalanceOracle::new(engine.clone());
         let store_path = cfg.state_path.clone();
+        let equity_log_path = cfg.equity_log_path.clone();
         let tz = cfg.tz.clone();
-        let trading_journal_path = cfg.trading_journal_path.clone();
-        let slippage_bps = cfg.slippage_bps;
-        let risk_params = risk_params.clone();
+        let summary_opts = SummaryOptions::from_config(&cfg)?;
+        let every = cfg.equity_interval_secs.max(10);
+        let params_rx = params_rx.clone();
 
         tokio::spawn(async move {
             let store = StateStore::new(store_path);
-            let mut interval = tokio::time::interval(std::time::Duration::from_secs(5));
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(every));
 
             loop {
                 interval.tick().await;
+                async {
+                    let _timer = crate::metrics::get().loop_duration.with_label_values(&["balance"]).start_timer();
+                    let _guard = crate::state::lock().await;
+                    let risk_params = params_rx.borrow().risk.clone();
+
+                    let mut st = match store.load() {
+                        Ok(Some(s)) => s,
+                        Ok(None) => return,
+                        Err(e) => {
+                            notifier_eq
+                                .alert_keyed(Severity::Critical, "state.load.balance", &format!("[SIE] state load failed (balance oracle): {e}"));
+                            return;
+                        }
+                    };
 
-                // Reload latest state each tick (simple & safe for now)
-                let mut st = match store.load() {
-                    Ok(Some(s)) => s,
-                    Ok(None) => PersistedState::new(RiskState::new(
-                        crate::time::day_key(&tz).unwrap_or_else(|_| "1970-01-01".into()),
-                        risk_params.capital_usdc,
-                    )),
-                    Err(e) => {
-                        let _ = notifier_pos
-                            .alert(&format!("[SIE] state load failed (positions loop): {e}"))
-                            .await;
-                        continue;
+                    if let Ok(dk) = crate::time::day_key(&tz) {
+                        rollover_day(&store, &mut st, dk, &notifier_eq, &summary_opts);
                     }
-                };
 
-                // Daily rollover
-                if let Ok(dk) = crate::time::day_key(&tz) {
-                    st.risk.rollover_day_if_needed(dk);
-                }
-
-                // If we ever reach EmergencyStop, liquidate immediately.
-                if st.risk.mode == BotMode::EmergencyStop {
-                    warn!("risk.emergency_stop.active");
-                    let _ = notifier_pos
-                        .alert("[SIE] EMERGENCY STOP active: closing all positions immediately")
-                        .await;
-
-                    // Liquidate sequentially; keep trying even if some closes fail.
-                    let mut idx = 0usize;
-                    while idx < st.positions.len() {
-                        let p = &st.positions[idx];
-                        match engine_pos
-                            .close_position_market(
-                                p.base_mint.clone(),
-                                p.quote_mint.clone(),
-                                p.base_amount,
-                            )
-                            .await
-                        {
-                            Ok(r) => {
-                                let _ = notifier_pos
-                                    .alert(&format!(
-                                        "[SIE] HARD STOP SELL {} tx={} ",
-                                        p.base_mint, r.signature
-                                    ))
-                                    .await;
-
-                                // Best-effort journal entry
-                                let _ = crate::journal::append_trade_close(
-                                    &trading_journal_path,
-                                    "hard-stop",
-                                    &format!("{}/{}", p.base_mint, p.quote_mint),
-                                    p.buy_tx.as_deref().unwrap_or(""),
-                                    &r.signature,
-                                    p.size_usdc,
-                                    0.0,
-                                    0.0,
-                                    crate::risk::ExitReason::HardStop,
-                                    "portfolio hard stop: emergency liquidation",
-                                    "N/A",
-                                );
-
-                                st.positions.remove(idx);
-                                continue;
-                            }
-                            Err(e) => {
-                                let _ = notifier_pos
-                                    .alert(&format!(
-                                        "[SIE] HARD STOP SELL FAILED {}: {e}",
-                                        p.base_mint
-                                    ))
-                                    .await;
-                                idx += 1;
-                            }
+                    let snap = match oracle.snapshot(&st.positions, &st.risk.daily.day_key).await {
+                        Ok(s) => s,
+                        Err(e) => {
+                            // Keep the previous equity; never trip limits on a failed valuation.
+                            warn!(error = %e, "balance.snapshot_failed");
+                            return;
                         }
+                    };
+
+                    if let Err(e) = crate::balance::append_snapshot(&equity_log_path, &snap) {
+                        error!(error = %e, "balance.log.write_failed");
+                    }
+
+                    let prev_mode = st.risk.mode;
+                    let ev = st.risk.register_equity(&risk_params, snap.equity_usdc);
+                    st.sync_mode_from_risk();
+                    info!(
+                        equity_usdc = snap.equity_usdc,
+                        usdc = snap.usdc,
+                        sol = snap.sol,
+                        positions_usdc = snap.positions_usdc,
+                        mode = ?st.risk.mode,
+                        "balance.snapshot"
+                    );
+
+                    if matches!(ev, RiskEvent::EnterReadOnly) {
+                        notifier_eq.event(Event::ModeChanged(ModeChanged::risk(
+                            prev_mode,
+                            BotMode::ReadOnly,
+                            format!(
+                                "daily loss limit reached on equity (equity=${:.2} day pnl=${:.2} limit=${:.2})",
+                                snap.equity_usdc,
+                                st.risk.daily_total_pnl_usdc(),
+                                risk_params.daily_loss_limit_usdc()
+                            ),
+                        )));
+                    }
+                    if matches!(ev, RiskEvent::EnterEmergencyStop) {
+                        notifier_eq.event(Event::ModeChanged(ModeChanged::risk(
+                            prev_mode,
+                            BotMode::EmergencyStop,
+                            format!("equity hard stop reached (equity=${:.2})", snap.equity_usdc),
+                        )));
                     }
 
                     if let Err(e) = store.save(&st) {
-                        let _ = notifier_pos
-                            .alert(&format!("[SIE] state save failed after hard stop liquidation: {e}"))
-                            .await;
+                        notifier_eq
+                            .alert_keyed(Severity::Critical, "state.save.balance", &format!("[SIE] state save failed (balance oracle): {e}"));
                     }
+                    crate::metrics::get().record_state(&st);
+                    crate::health::tick("balance");
                 }
+                .instrument(info_span!("balance.tick"))
+                .await;
+            }
+        });
+    } else {
+        warn!("balance.oracle.disabled: SOL_KEYPAIR_PATH not set, risk uses configured capital");
+    }
+
+    // Positions loop every 5s
+    {
+        let notifier_pos = notifier.clone();
+        let engine_pos = engine.clone();
+        let store_path = cfg.state_path.clone();
+        let tz = cfg.tz.clone();
+        let summary_opts = SummaryOptions::from_config(&cfg)?;
+        let trading_journal_path = cfg.trading_journal_path.clone();
+        let trade_ledger_path = cfg.trade_ledger_path.clone();
+        let params_rx = params_rx.clone();
+
+        tokio::spawn(async move {
+            let store = StateStore::new(store_path);
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(5));
 
-                // Monitor open positions: compute price and enforce SL/TP/trailing.
-                // Exits are allowed even in READ_ONLY.
-                let mut closed_any = false;
-                let mut i = 0usize;
-                while i < st.positions.len() {
-                    let mut close_reason = None;
-
-                    let p = &mut st.positions[i];
-                    let price = match engine_pos
-                        .price_quote_per_base(&p.base_mint, &p.quote_mint)
-                        .await
-                    {
-                        Ok(px) => px,
+            loop {
+                interval.tick().await;
+                async {
+                    let _timer = crate::metrics::get().loop_duration.with_label_values(&["positions"]).start_timer();
+                    let _guard = crate::state::lock().await;
+                    let LiveParams {
+                        risk: risk_params,
+                        force_reduce_on_daily_loss: force_reduce,
+                        ..
+                    } = params_rx.borrow().clone();
+
+                    // Reload latest state each tick (simple & safe for now)
+                    let mut st = match store.load() {
+                        Ok(Some(s)) => s,
+                        Ok(None) => PersistedState::new(RiskState::new(
+                            crate::time::day_key(&tz).unwrap_or_else(|_| "1970-01-01".into()),
+                            risk_params.capital_usdc,
+                        )),
                         Err(e) => {
-                            let _ = notifier_pos
-                                .alert(&format!("[SIE] price fetch failed for {}: {e}", p.base_mint))
-                                .await;
-                            i += 1;
-                            continue;
+                            notifier_pos
+                                .alert_keyed(Severity::Critical, "state.load.positions", &format!("[SIE] state load failed (positions loop): {e}"));
+                            return;
                         }
                     };
+                    tracing::Span::current().record("open_positions", st.positions.len());
 
-                    // update peak
-                    if price > p.peak_price {
-                        p.peak_price = price;
+                    // Daily rollover
+                    if let Ok(dk) = crate::time::day_key(&tz) {
+                        rollover_day(&store, &mut st, dk, &notifier_pos, &summary_opts);
                     }
 
-                    let pnl_pct = (price - p.entry_price) / p.entry_price;
-                    if !p.trailing_armed && pnl_pct >= p.trailing_arm_pct {
-                        p.trailing_armed = true;
-                    }
+                    // If we ever reach EmergencyStop, liquidate immediately.
+                    if st.risk.mode == BotMode::EmergencyStop {
+                        warn!("risk.emergency_stop.active");
+                        notifier_pos
+                            .alert(Severity::Critical, "[SIE] EMERGENCY STOP active: closing all positions immediately");
+
+                        // Liquidate sequentially; keep trying even if some closes fail.
+                        let ctx = crate::exits::ExitContext {
+                            engine: &engine_pos,
+                            notifier: &notifier_pos,
+                            risk_params: &risk_params,
+                            trading_journal_path: &trading_journal_path,
+                            trade_ledger_path: &trade_ledger_path,
+                        };
+                        let mut idx = 0usize;
+                        while idx < st.positions.len() {
+                            crate::health::progress();
+                            let p = &st.positions[idx];
+                            if p.pending_sell.is_some() || crate::exits::is_closing(&p.id) {
+                                // Already selling; settled in the pass below or by its owner.
+                                idx += 1;
+                                continue;
+                            }
+                            match crate::exits::close_at_market(
+                                &ctx,
+                                &mut st,
+                                idx,
+                                crate::risk::ExitReason::HardStop,
+                                "portfolio hard stop: emergency liquidation",
+                            )
+                            .await
+                            {
+                                Ok(CloseOutcome::Closed { .. }) => continue,
+                                Ok(CloseOutcome::Pending { .. }) => idx += 1,
+                                Err(e) => {
+                                    notifier_pos
+                                        .alert(Severity::Critical, &format!(
+                                            "[SIE] HARD STOP SELL FAILED {}: {e}",
+                                            st.positions[idx].base_mint
+                                        ));
+                                    idx += 1;
+                                }
+                            }
+                        }
 
-                    let stop_price = if p.trailing_armed {
-                        p.peak_price * (1.0 - p.stop_loss_pct)
-                    } else {
-                        p.entry_price * (1.0 - p.stop_loss_pct)
-                    };
-                    let tp_price = p.entry_price * (1.0 + p.take_profit_pct);
-
-                    if price <= stop_price {
-                        close_reason = Some(if p.trailing_armed {
-                            crate::risk::ExitReason::TrailingStop
-                        } else {
-                            crate::risk::ExitReason::StopLoss
-                        });
-                    } else if price >= tp_price {
-                        close_reason = Some(crate::risk::ExitReason::TakeProfit);
+                        if let Err(e) = store.save(&st) {
+                            notifier_pos
+                                .alert(Severity::Critical, &format!("[SIE] state save failed after hard stop liquidation: {e}"));
+                        }
                     }
 
-                    if let Some(reason) = close_reason {
-                        // Market exit: sell base -> quote.
-                        let res = engine_pos
-                            .execute_swap(crate::engine::SwapPlan {
-                                input_mint: p.base_mint.clone(),
-                                output_mint: p.quote_mint.clone(),
-                                in_amount: p.base_amount,
-                                slippage_bps,
-                            })
-                            .await;
-
-                        match res {
-                            Ok(r) => {
-                                p.sell_tx = Some(r.signature.clone());
-
-                                // Realized pnl estimate based on current price.
-                                // base tokens (approx) = base_amount / 10^decimals, but we don't persist decimals yet.
-                                // We approximate with entry size in USDC for accounting scaffold.
-                                let est_exit_usdc = p.size_usdc * (1.0 + pnl_pct);
-                                let pnl_usdc = est_exit_usdc - p.size_usdc;
-
-                                let ev = st.risk.register_realized_pnl(&risk_params, pnl_usdc);
-                                st.sync_mode_from_risk();
-
-                                let _ = notifier_pos
-                                    .alert(&format!(
-                                        "[SIE] SELL {} reason={:?} pnl=${:.2} ({:.2}%) tx={} mode={:?}",
-                                        p.base_mint,
-                                        reason,
-                                        pnl_usdc,
-                                        pnl_pct * 100.0,
-                                        r.signature,
-                                        st.risk.mode
-                                    ))
-                                    .await;
-
-                                // Journal append (best-effort)
-                                let _ = crate::journal::append_trade_close(
-                                    &trading_journal_path,
-                                    "momentum-scalping", // placeholder until strategy tags positions
-                                    &format!("{}/{}", p.base_mint, p.quote_mint),
-                                    p.buy_tx.as_deref().unwrap_or(""),
-                                    p.sell_tx.as_deref().unwrap_or(""),
-                                    p.size_usdc,
-                                    pnl_usdc,
-                                    pnl_pct,
-                                    reason,
-                                    "auto-exit via risk rules",
-                                    "N/A (pricefeed scaffold)",
-                                );
-
-                                // Remove position
-                                st.positions.remove(i);
-                                closed_any = true;
-
-                                // React to mode transitions.
-                                if matches!(ev, RiskEvent::EnterReadOnly) {
-                                    let _ = notifier_pos
-                                        .alert("[SIE] READ_ONLY entered: daily loss limit reached")
-                                        .await;
-                                }
-                                if matches!(ev, RiskEvent::EnterEmergencyStop) {
-                                    let _ = notifier_pos
-                                        .alert("[SIE] EMERGENCY STOP entered: portfolio hard stop reached")
-                                        .await;
-                                    // Liquidate remaining positions ASAP (loop continues)
+                    // Monitor open positions: compute price and enforce SL/TP/trailing.
+                    // Exits are allowed even in READ_ONLY.
+                    let ctx = crate::exits::ExitContext {
+                        engine: &engine_pos,
+                        notifier: &notifier_pos,
+                        risk_params: &risk_params,
+                        trading_journal_path: &trading_journal_path,
+                        trade_ledger_path: &trade_ledger_path,
+                    };
+                    let mut closed_any = false;
+                    // (position id, price, unrealized pnl) for positions that stay open
+                    let mut marks: Vec<(String, f64, f64)> = vec![];
+                    let mut i = 0usize;
+                    while i < st.positions.len() {
+                        crate::health::progress();
+                        if st.positions[i].pending_sell.is_some() {
+                            match crate::exits::settle_pending_sell(&ctx, &mut st, i).await {
+                                Ok(true) => {
+                                    closed_any = true;
+                                    continue; // landed: removed
                                 }
-
-                                continue; // do not increment i (we removed current)
+                                Ok(false) => {}
+                                Err(e) => warn!(error = %e, id = %st.positions[i].id, "exits.pending_check_failed"),
                             }
+                        }
+
+                        let p = &mut st.positions[i];
+                        let price = match engine_pos
+                            .price_quote_per_base(&p.base_mint, &p.quote_mint)
+                            .await
+                        {
+                            Ok(px) => px,
                             Err(e) => {
-                                let _ = notifier_pos
-                                    .alert(&format!("[SIE] SELL failed for {}: {e}", p.base_mint))
-                                    .await;
+                                notifier_pos
+                                    .alert_keyed(
+                                        Severity::Warning,
+                                        &format!("price_fetch:{}", p.base_mint),
+                                        &format!("[SIE] price fetch failed for {}: {e}", p.base_mint),
+                                    );
+                                // No exit on a stale price, but keep its loss in the daily rule.
+                                let last = p.mark_price.unwrap_or(p.entry_price);
+                                let pnl_pct = (last - p.entry_price) / p.entry_price;
+                                marks.push((p.id.clone(), last, p.size_usdc * pnl_pct));
                                 i += 1;
                                 continue;
                             }
+                        };
+                        p.mark_price = Some(price);
+
+                        // A position with a sell in flight keeps its mark but doesn't sell again.
+                        let selling = p.pending_sell.is_some() || crate::exits::is_closing(&p.id);
+                        let signal = if !selling { crate::exits::exit_signal(p, price) } else { None };
+                        if let Some(reason) = signal {
+                            match crate::exits::close_position(&ctx, &mut st, i, reason, price, "auto-exit via risk rules")
+                                .await
+                            {
+                                Ok(CloseOutcome::Closed { .. }) => {
+                                    closed_any = true;
+                                    continue; // do not increment i (we removed current)
+                                }
+                                Ok(CloseOutcome::Pending { .. }) => closed_any = true,
+                                Err(e) => {
+                                    let mint = &st.positions[i].base_mint;
+                                    notifier_pos
+                                        .alert_keyed(
+                                            Severity::Critical,
+                                            &format!("sell_failed:{mint}"),
+                                            &format!("[SIE] SELL failed for {mint}: {e}"),
+                                        );
+                                }
+                            }
                         }
+
+                        let p = &st.positions[i];
+                        let pnl_pct = (price - p.entry_price) / p.entry_price;
+                        marks.push((p.id.clone(), price, p.size_usdc * pnl_pct));
+                        i += 1;
                     }
 
-                    i += 1;
-                }
+                    // Daily loss rule on realized + unrealized PnL.
+                    let unrealized_usdc: f64 = marks.iter().map(|(_, _, pnl)| pnl).sum();
+                    let prev_mode = st.risk.mode;
+                    let ev = st.risk.register_unrealized_pnl(&risk_params, unrealized_usdc);
+                    st.sync_mode_from_risk();
+                    if matches!(ev, RiskEvent::EnterReadOnlyUnrealized) {
+                        notifier_pos.event(Event::ModeChanged(ModeChanged::risk(
+                            prev_mode,
+                            BotMode::ReadOnly,
+                            format!(
+                                "daily loss limit reached incl. open positions (realized=${:.2} unrealized=${:.2} limit=${:.2})",
+                                st.risk.daily.realized_pnl_usdc,
+                                unrealized_usdc,
+                                risk_params.daily_loss_limit_usdc()
+                            ),
+                        )));
+                    }
 
-                if closed_any {
-                    if let Err(e) = store.save(&st) {
-                        let _ = notifier_pos
-                            .alert(&format!("[SIE] state save failed after closes: {e}"))
-                            .await;
+                    // Optionally cut exposure: close the worst losing position each tick while breached.
+                    if force_reduce && st.risk.daily_loss_breached(&risk_params) {
+                        let selling = |id: &String| {
+                            crate::exits::is_closing(id)
+                                || st.positions.iter().any(|p| &p.id == id && p.pending_sell.is_some())
+                        };
+                        let worst = marks
+                            .iter()
+                            .filter(|(id, _, pnl)| *pnl < 0.0 && !selling(id))
+                            .min_by(|a, b| a.2.total_cmp(&b.2));
+                        if let Some((id, price, _)) = worst {
+                            if let Some(idx) = st.positions.iter().position(|p| &p.id == id) {
+                                crate::health::progress();
+                                match crate::exits::close_position(
+                                    &ctx,
+                                    &mut st,
+                                    idx,
+                                    crate::risk::ExitReason::DailyLossLimit,
+                                    *price,
+                                    "force-reduce: daily loss limit incl. open positions",
+                                )
+                                .await
+                                {
+                                    Ok(_) => closed_any = true,
+                                    Err(e) => {
+                                        notifier_pos
+                                            .alert(Severity::Critical, &format!("[SIE] force-reduce SELL failed for {id}: {e}"));
+                                    }
+                                }
+                            }
+                        }
                     }
-                } else {
-                    st.sync_mode_from_risk();
-                    if let Err(e) = store.save(&st) {
-                        let _ = notifier_pos
-                            .alert(&format!("[SIE] state save failed (positions loop): {e}"))
-                            .await;
+
+                    if closed_any {
+                        if let Err(e) = store.save(&st) {
+                            notifier_pos
+                                .alert(Severity::Critical, &format!("[SIE] state save failed after closes: {e}"));
+                        }
+                    } else {
+                        st.sync_mode_from_risk();
+                        if let Err(e) = store.save(&st) {
+                            notifier_pos
+                                .alert_keyed(Severity::Critical, "state.save.positions", &format!("[SIE] state save failed (positions loop): {e}"));
+                        }
                     }
+                    crate::metrics::get().record_state(&st);
+                    crate::health::tick("positions");
                 }
+                .instrument(info_span!("positions.tick", open_positions = tracing::field::Empty))
+                .await;
             }
         });
     }
@@ -330,114 +498,278 @@ async fn main() -> Result<()> {
         let notifier_mkt = notifier.clone();
         let store_path = cfg.state_path.clone();
         let tz = cfg.tz.clone();
-        let risk_params = risk_params.clone();
+        let summary_opts = SummaryOptions::from_config(&cfg)?;
+        let params_rx = params_rx.clone();
 
         tokio::spawn(async move {
             let store = StateStore::new(store_path);
             loop {
                 let sleep_s: u64 = thread_rng().gen_range(10..=20);
                 tokio::time::sleep(std::time::Duration::from_secs(sleep_s)).await;
+                async {
+                    let _timer = crate::metrics::get().loop_duration.with_label_values(&["market"]).start_timer();
+                    let _guard = crate::state::lock().await;
+                    let LiveParams {
+                        risk: risk_params,
+                        momentum: momentum_cfg,
+                        ..
+                    } = params_rx.borrow().clone();
+
+                    let mut st = match store.load() {
+                        Ok(Some(s)) => s,
+                        Ok(None) => PersistedState::new(RiskState::new(
+                            crate::time::day_key(&tz).unwrap_or_else(|_| "1970-01-01".into()),
+                            risk_params.capital_usdc,
+                        )),
+                        Err(e) => {
+                            notifier_mkt
+                                .alert_keyed(Severity::Critical, "state.load.market", &format!("[SIE] state load failed (market loop): {e}"));
+                            return;
+                        }
+                    };
 
-                let mut st = match store.load() {
-                    Ok(Some(s)) => s,
-                    Ok(None) => PersistedState::new(RiskState::new(
-                        crate::time::day_key(&tz).unwrap_or_else(|_| "1970-01-01".into()),
-                        risk_params.capital_usdc,
-                    )),
-                    Err(e) => {
-                        let _ = notifier_mkt
-                            .alert(&format!("[SIE] state load failed (market loop): {e}"))
-                            .await;
-                        continue;
+                    if let Ok(dk) = crate::time::day_key(&tz) {
+                        rollover_day(&store, &mut st, dk, &notifier_mkt, &summary_opts);
                     }
-                };
 
-                if let Ok(dk) = crate::time::day_key(&tz) {
-                    st.risk.rollover_day_if_needed(dk);
-                }
+                    // Enforce read-only: no new positions.
+                    if st.risk.mode != BotMode::Trading {
+                        crate::health::tick("market");
+                        return;
+                    }
 
-                // Enforce read-only: no new positions.
-                if st.risk.mode != BotMode::Trading {
-                    continue;
-                }
+                    // Strategy (momentum scalping) - DRY-RUN scaffold.
+                    // NOTE: We still don't have candle/volume feed wired, so this emits no intents.
+                    let mut scalper = crate::strategy::momentum::MomentumScalper::new(
+                        crate::balance::USDC_MINT.to_string(),
+                        risk_params.position_size_usdc,
+                        momentum_cfg.watchlist.clone(),
+                    );
+                    scalper.params = crate::strategy::momentum::MomentumParams {
+                        rsi_period: momentum_cfg.rsi_period,
+                        rsi_breakout: momentum_cfg.rsi_breakout,
+                        min_volume_usdc_1m: momentum_cfg.min_volume_usdc_1m,
+                    };
+                    let intents = if momentum_cfg.enabled { scalper.evaluate() } else { Ok(vec![]) };
+                    if let Ok(intents) = intents {
+                        for intent in &intents {
+                            let _span = info_span!(
+                                "intent",
+                                strategy = ?intent.strategy,
+                                base_mint = %intent.base_mint,
+                                quote_mint = %intent.quote_mint,
+                                size_usdc = intent.size_usdc
+                            )
+                            .entered();
+                            info!(notes = ?intent.notes, "strategy.intent");
+                        }
+                        if !intents.is_empty() {
+                            notifier_mkt
+                                .alert(Severity::Info, &format!("[SIE] momentum intents: {}", intents.len()));
+                        }
+                    }
+
+                    // TODO next:
+                    // - wire candle source (1m/5m) and volume breakout
+                    // - risk gate + max positions
+                    // - call engine.execute_swap (simulateTransaction mandatory)
 
-                // Strategy (momentum scalping) - DRY-RUN scaffold.
-                // NOTE: We still don't have candle/volume feed wired, so this emits no intents.
-                let scalper = crate::strategy::momentum::MomentumScalper::new(
-                    // USDC mint (mainnet)
-                    "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
-                    risk_params.position_size_usdc,
-                    vec![],
-                );
-                if let Ok(intents) = scalper.evaluate() {
-                    if !intents.is_empty() {
-                        let _ = notifier_mkt
-                            .alert(&format!("[SIE] momentum intents: {}", intents.len()))
-                            .await;
+                    st.sync_mode_from_risk();
+                    if let Err(e) = store.save(&st) {
+                        notifier_mkt
+                            .alert_keyed(Severity::Critical, "state.save.market", &format!("[SIE] state save failed (market loop): {e}"));
                     }
+                    crate::health::tick("market");
                 }
+                .instrument(info_span!("market.tick"))
+                .await;
+            }
+        });
+    }
 
-                // TODO next:
-                // - wire candle source (1m/5m) and volume breakout
-                // - risk gate + max positions
-                // - call engine.execute_swap (simulateTransaction mandatory)
+    // Prometheus metrics, health checks, RPC probe and systemd watchdog
+    {
+        let addr = cfg.monitoring_addr.clone();
+        let notifier_mon = notifier.clone();
+        tokio::spawn(crate::health::probe_rpc(engine.clone()));
+        tokio::spawn(crate::health::watchdog(max_tick_age));
+        tokio::spawn(async move {
+            if let Err(e) = crate::monitoring::serve(&addr, max_tick_age).await {
+                error!(error = %e, "monitoring.failed");
+                notifier_mon.alert(Severity::Warning, &format!("[SIE] metrics endpoint stopped: {e}"));
+            }
+        });
+    }
+
+    // Operator actions, shared by the control API and chat commands.
+    let control = crate::control::Control::new(engine.clone(), notifier.clone(), params_rx.clone());
+
+    // Operator control API (loopback, token-authenticated)
+    match cfg.control_tokens.as_ref().map(|t| t.expose()) {
+        Some(raw) => {
+            let srv = crate::control::ControlServer::new(control.clone(), crate::control::parse_operator_tokens(raw)?);
+            let addr = cfg.control_addr.clone();
+            let notifier_ctl = notifier.clone();
+            tokio::spawn(async move {
+                if let Err(e) = crate::control::serve(&addr, srv).await {
+                    error!(error = %e, "control.api.failed");
+                    notifier_ctl.alert(Severity::Warning, &format!("[SIE] control API stopped: {e}"));
+                }
+            });
+        }
+        None => warn!("control.api.disabled: SIE_CONTROL_TOKENS not set"),
+    }
 
-                st.sync_mode_from_risk();
-                if let Err(e) = store.save(&st) {
-                    let _ = notifier_mkt
-                        .alert(&format!("[SIE] state save failed (market loop): {e}"))
-                        .await;
+    // Chat commands (Slack slash commands, Telegram bot polling)
+    if cfg.slack_signing_secret.is_some() || cfg.telegram_commands {
+        let ops = std::sync::Arc::new(crate::chatops::ChatOps::from_config(&cfg, control.clone())?);
+        if let Some(secret) = cfg.slack_signing_secret.clone() {
+            let addr = cfg.chatops_addr.clone();
+            let ops = ops.clone();
+            let notifier_chat = notifier.clone();
+            tokio::spawn(async move {
+                if let Err(e) = crate::chatops::slack::serve(&addr, ops, secret).await {
+                    error!(error = %e, "chatops.slack.failed");
+                    notifier_chat.alert(Severity::Warning, &format!("[SIE] Slack commands endpoint stopped: {e}"));
+                }
+            });
+        }
+        if let (true, Some(token)) = (cfg.telegram_commands, cfg.telegram_bot_token.clone()) {
+            let notifier_chat = notifier.clone();
+            tokio::spawn(async move {
+                if let Err(e) = crate::chatops::telegram::poll(token, ops).await {
+                    error!(error = %e, "chatops.telegram.failed");
+                    notifier_chat.alert(Severity::Warning, &format!("[SIE] Telegram commands stopped: {e}"));
                 }
+            });
+        }
+    }
+
+    // Config hot reload
+    {
+        let cfg_reload = cfg.clone();
+        let notifier_rl = notifier.clone();
+        tokio::spawn(async move {
+            if let Err(e) = crate::reload::watch_config(cfg_reload, hup, params_tx, notifier_rl.clone()).await {
+                error!(error = %e, "config.reload.failed");
+                notifier_rl.alert(Severity::Warning, &format!("[SIE] config reload watcher stopped: {e}"));
             }
         });
     }
 
     // Boot notice
     notifier
-        .alert(&format!(
+        .alert(Severity::Info, &format!(
             "[SIE] daemon started (mode={:?}, dry_run={})",
             st.risk.mode, cfg.dry_run
-        ))
-        .await
-        .ok();
+        ));
+    crate::health::notify_ready();
 
     // Main: persist state periodically + watch for mode transitions
+    let summary_opts = SummaryOptions::from_config(&cfg)?;
     let mut interval = tokio::time::interval(std::time::Duration::from_secs(30));
     loop {
         interval.tick().await;
+        async {
+            let _timer = crate::metrics::get().loop_duration.with_label_values(&["main"]).start_timer();
+            // Digests for suppressed repeats whose window closed.
+            notifier.flush_digests();
+            let _guard = crate::state::lock().await;
+            let risk_params = params_rx.borrow().risk.clone();
+
+            // Reload: other loops and the control API write state.json too.
+            match store.load() {
+                Ok(Some(s)) => st = s,
+                Ok(None) => {}
+                Err(e) => {
+                    error!(error = %e, "state.load_failed");
+                    return;
+                }
+            }
 
-        // Daily rollover
-        let new_day_key = crate::time::day_key(&cfg.tz).unwrap_or_else(|_| day_key.clone());
-        st.risk.rollover_day_if_needed(new_day_key);
-
-        // If risk state says enter read-only/emergency, alert.
-        match st.risk.mode {
-            BotMode::ReadOnly => {
-                notifier
-                    .alert(&format!(
-                        "[SIE] READ_ONLY: daily loss limit breached (limit=${:.2})",
-                        risk_params.daily_loss_limit_usdc()
-                    ))
-                    .await
-                    .ok();
+            // Daily rollover
+            let new_day_key = crate::time::day_key(&cfg.tz).unwrap_or_else(|_| day_key.clone());
+            rollover_day(&store, &mut st, new_day_key, &notifier, &summary_opts);
+
+            // If risk state says enter read-only/emergency, alert.
+            match st.risk.mode {
+                BotMode::ReadOnly => {
+                    notifier
+                        .alert_keyed(Severity::Warning, "mode.read_only", &format!(
+                            "[SIE] READ_ONLY: daily loss limit breached (limit=${:.2})",
+                            risk_params.daily_loss_limit_usdc()
+                        ));
+                }
+                BotMode::EmergencyStop => {
+                    notifier
+                        .alert_keyed(
+                            Severity::Critical,
+                            "mode.emergency_stop",
+                            "[SIE] EMERGENCY STOP: portfolio hard stop breached",
+                        );
+                }
+                BotMode::Trading => {}
             }
-            BotMode::EmergencyStop => {
-                notifier
-                    .alert("[SIE] EMERGENCY STOP: portfolio hard stop breached")
-                    .await
-                    .ok();
+
+            // Persist what the notifier counted for the next summary.
+            st.summary.day.absorb(notifier.take_tally());
+            st.sync_mode_from_risk();
+            if let Err(e) = store.save(&st) {
+                error!(error = %e, "state.save_failed");
             }
-            BotMode::Trading => {}
-        }
+            crate::metrics::get().record_state(&st);
+            crate::health::tick("main");
 
-        st.sync_mode_from_risk();
-        if let Err(e) = store.save(&st) {
-            error!(error = %e, "state.save_failed");
-        }
+            info!(mode = ?st.risk.mode, open_positions = st.positions.len(), "tick");
 
-        info!(mode = ?st.risk.mode, open_positions = st.positions.len(), "tick");
+            // Keep risk events placeholder used (for future wiring on trade close)
+            let _ = RiskEvent::None;
+        }
+        .instrument(info_span!("main.tick"))
+        .await;
+    }
+}
 
-        // Keep risk events placeholder used (for future wiring on trade close)
-        let _ = RiskEvent::None;
+/// Starts a new trading day if `day_key` changed and sends the summary of the
+/// day that ended. Saves right away: several loops roll over, and one that
+/// bails out before its own save must not leave the next loop to repeat it.
+fn rollover_day(
+    store: &StateStore,
+    st: &mut PersistedState,
+    day_key: String,
+    notifier: &Notifier,
+    opts: &SummaryOptions,
+) {
+    let Some(closed) = st.risk.rollover_day_if_needed(day_key.clone()) else {
+        return;
+    };
+    st.summary.day.absorb(notifier.take_tally());
+    let summaries = crate::summary::close_day(
+        &mut st.summary,
+        opts,
+        closed,
+        &day_key,
+        st.positions.len(),
+        st.risk.mode,
+    );
+    if let Err(e) = store.save(st) {
+        notifier.alert_keyed(
+            Severity::Critical,
+            "state.save.rollover",
+            &format!("[SIE] state save failed at day rollover: {e}"),
+        );
+    }
+    for s in summaries {
+        info!(
+            period = ?s.period,
+            day = %s.day_key,
+            realized_pnl_usdc = s.realized_pnl_usdc,
+            trades = s.trades,
+            "risk.day_closed"
+        );
+        if let Err(e) = crate::journal::append_summary(&opts.trading_journal_path, &s, &opts.journal) {
+            warn!(error = %e, "journal.write_failed");
+        }
+        notifier.event(Event::DailySummary(s));
     }
 }
@@ -0,0 +1,90 @@
+use anyhow::Result;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+
+/// Wrapped SOL mint; its stats stand in for the market backdrop.
+pub const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+
+/// Token stats from DexScreener (`GET /tokens/v1/solana/{mint}`), taken from
+/// the most liquid pair.
+#[derive(Clone)]
+pub struct MarketClient {
+    base_url: String,
+    http: Client,
+}
+
+/// Market stats of one token. Changes are fractions (0.05 = +5%).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TokenStats {
+    pub price_usd: Option<f64>,
+    pub price_change_1h_pct: Option<f64>,
+    pub price_change_24h_pct: Option<f64>,
+    pub volume_1h_usd: Option<f64>,
+    pub volume_24h_usd: Option<f64>,
+    pub liquidity_usd: Option<f64>,
+}
+
+/// Market backdrop when a trade closed: SOL and the traded token.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MarketContext {
+    pub sol: Option<TokenStats>,
+    pub token: Option<TokenStats>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Pair {
+    #[serde(rename = "priceUsd")]
+    price_usd: Option<String>,
+    #[serde(rename = "priceChange", default)]
+    price_change: Window,
+    #[serde(default)]
+    volume: Window,
+    liquidity: Option<Liquidity>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Window {
+    h1: Option<f64>,
+    h24: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Liquidity {
+    usd: Option<f64>,
+}
+
+impl MarketClient {
+    pub fn new(base_url: String) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http: Client::new(),
+        }
+    }
+
+    /// Stats of the most liquid Solana pair of `mint`; `None` if it has no pairs.
+    pub async fn token_stats(&self, mint: &str) -> Result<Option<TokenStats>> {
+        let url = format!("{}/tokens/v1/solana/{mint}", self.base_url);
+        let pairs: Vec<Pair> = self.http.get(url).send().await?.error_for_status()?.json().await?;
+        let liquidity = |p: &Pair| p.liquidity.as_ref().and_then(|l| l.usd).unwrap_or(0.0);
+        Ok(pairs
+            .into_iter()
+            .max_by(|a, b| liquidity(a).total_cmp(&liquidity(b)))
+            .map(|p| TokenStats {
+                price_usd: p.price_usd.and_then(|v| v.parse().ok()),
+                price_change_1h_pct: p.price_change.h1.map(|v| v / 100.0),
+                price_change_24h_pct: p.price_change.h24.map(|v| v / 100.0),
+                volume_1h_usd: p.volume.h1,
+                volume_24h_usd: p.volume.h24,
+                liquidity_usd: p.liquidity.and_then(|l| l.usd),
+            }))
+    }
+}
+
+impl TokenStats {
+    /// Last hour's volume against the 24h hourly average (1.0 = average).
+    pub fn volume_1h_vs_avg(&self) -> Option<f64> {
+        let last_hour = self.volume_1h_usd?;
+        let avg = self.volume_24h_usd? / 24.0;
+        (avg > 0.0).then(|| last_hour / avg)
+    }
+}
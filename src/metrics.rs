@@ -0,0 +1,153 @@
+use std::future::Future;
+use std::sync::LazyLock;
+
+use prometheus::{
+    histogram_opts, opts, Encoder, Gauge, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
+    Registry, TextEncoder,
+};
+
+use crate::risk::BotMode;
+use crate::state::PersistedState;
+
+/// Process-wide metrics, served as Prometheus text on `/metrics`.
+pub struct Metrics {
+    registry: Registry,
+    /// 1 for the current mode, 0 for the others.
+    pub mode: IntGaugeVec,
+    pub open_positions: IntGauge,
+    pub equity_usdc: Gauge,
+    pub daily_realized_pnl_usdc: Gauge,
+    pub daily_unrealized_pnl_usdc: Gauge,
+    /// `stage`: attempted | simulated | failed | landed (confirmed) | unconfirmed (sent, timed out).
+    pub swaps: IntCounterVec,
+    pub quote_latency: Histogram,
+    /// `endpoint`: RPC method (`getBalance`, ...) or Jupiter call (`jupiter.quote`).
+    pub rpc_requests: IntCounterVec,
+    pub rpc_errors: IntCounterVec,
+    /// Compute unit price requested on the last swap.
+    pub priority_fee_micro_lamports: IntGauge,
+    /// Priority fee of swaps sent, as reported by Jupiter.
+    pub priority_fee_lamports: IntCounter,
+    /// `loop`: positions | market | balance | main.
+    pub loop_duration: HistogramVec,
+    /// Unix time of each loop's last completed iteration.
+    pub loop_last_tick: IntGaugeVec,
+}
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(|| Metrics::new().expect("metric definitions are valid"));
+
+pub fn get() -> &'static Metrics {
+    &METRICS
+}
+
+impl Metrics {
+    fn new() -> prometheus::Result<Self> {
+        let registry = Registry::new_custom(Some("solbot".into()), None)?;
+        let m = Self {
+            mode: IntGaugeVec::new(opts!("mode", "Current bot mode (1 = active)"), &["mode"])?,
+            open_positions: IntGauge::new("open_positions", "Open positions")?,
+            equity_usdc: Gauge::new("equity_usdc", "Last marked-to-market equity (USDC)")?,
+            daily_realized_pnl_usdc: Gauge::new("daily_realized_pnl_usdc", "Realized PnL of the trading day (USDC)")?,
+            daily_unrealized_pnl_usdc: Gauge::new(
+                "daily_unrealized_pnl_usdc",
+                "Mark-to-market PnL of open positions (USDC)",
+            )?,
+            swaps: IntCounterVec::new(opts!("swaps_total", "Swaps by pipeline stage reached"), &["stage"])?,
+            quote_latency: Histogram::with_opts(histogram_opts!(
+                "quote_latency_seconds",
+                "Jupiter quote round-trip time",
+                vec![0.05, 0.1, 0.25, 0.5, 1.0, 2.0, 5.0, 10.0]
+            ))?,
+            rpc_requests: IntCounterVec::new(opts!("rpc_requests_total", "RPC / API calls"), &["endpoint"])?,
+            rpc_errors: IntCounterVec::new(opts!("rpc_errors_total", "Failed RPC / API calls"), &["endpoint"])?,
+            priority_fee_micro_lamports: IntGauge::new(
+                "priority_fee_micro_lamports_per_cu",
+                "Compute unit price requested on the last swap",
+            )?,
+            priority_fee_lamports: IntCounter::new(
+                "priority_fee_lamports_total",
+                "Priority fees of swaps sent (lamports)",
+            )?,
+            loop_duration: HistogramVec::new(
+                histogram_opts!(
+                    "loop_duration_seconds",
+                    "Duration of one loop iteration",
+                    vec![0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0]
+                ),
+                &["loop"],
+            )?,
+            loop_last_tick: IntGaugeVec::new(
+                opts!("loop_last_tick_timestamp_seconds", "Last completed loop iteration (unix time)"),
+                &["loop"],
+            )?,
+            registry,
+        };
+
+        m.registry.register(Box::new(m.mode.clone()))?;
+        m.registry.register(Box::new(m.open_positions.clone()))?;
+        m.registry.register(Box::new(m.equity_usdc.clone()))?;
+        m.registry.register(Box::new(m.daily_realized_pnl_usdc.clone()))?;
+        m.registry.register(Box::new(m.daily_unrealized_pnl_usdc.clone()))?;
+        m.registry.register(Box::new(m.swaps.clone()))?;
+        m.registry.register(Box::new(m.quote_latency.clone()))?;
+        m.registry.register(Box::new(m.rpc_requests.clone()))?;
+        m.registry.register(Box::new(m.rpc_errors.clone()))?;
+        m.registry.register(Box::new(m.priority_fee_micro_lamports.clone()))?;
+        m.registry.register(Box::new(m.priority_fee_lamports.clone()))?;
+        m.registry.register(Box::new(m.loop_duration.clone()))?;
+        m.registry.register(Box::new(m.loop_last_tick.clone()))?;
+
+        // Export every series from the start so dashboards don't show gaps.
+        for stage in ["attempted", "simulated", "failed", "landed", "unconfirmed"] {
+            m.swaps.with_label_values(&[stage]);
+        }
+        Ok(m)
+    }
+
+    /// Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut buf = vec![];
+        if let Err(e) = TextEncoder::new().encode(&self.registry.gather(), &mut buf) {
+            tracing::warn!(error = %e, "metrics.encode_failed");
+        }
+        String::from_utf8(buf).unwrap_or_default()
+    }
+
+    /// Updates the mode, position and PnL gauges from a freshly saved state.
+    pub fn record_state(&self, st: &PersistedState) {
+        for mode in [BotMode::Trading, BotMode::ReadOnly, BotMode::EmergencyStop] {
+            self.mode
+                .with_label_values(&[mode_label(mode)])
+                .set((st.risk.mode == mode) as i64);
+        }
+        self.open_positions.set(st.positions.len() as i64);
+        if let Some(eq) = st.risk.equity_usdc {
+            self.equity_usdc.set(eq);
+        }
+        self.daily_realized_pnl_usdc.set(st.risk.daily.realized_pnl_usdc);
+        self.daily_unrealized_pnl_usdc.set(st.risk.daily.unrealized_pnl_usdc + 0.0); // no "-0"
+    }
+
+    pub fn swap_stage(&self, stage: &str) {
+        self.swaps.with_label_values(&[stage]).inc();
+    }
+}
+
+fn mode_label(mode: BotMode) -> &'static str {
+    match mode {
+        BotMode::Trading => "trading",
+        BotMode::ReadOnly => "read_only",
+        BotMode::EmergencyStop => "emergency_stop",
+    }
+}
+
+/// Counts one call to `endpoint`, and its failure if it fails.
+pub async fn rpc<T, E>(endpoint: &str, call: impl Future<Output = Result<T, E>>) -> Result<T, E> {
+    let m = get();
+    m.rpc_requests.with_label_values(&[endpoint]).inc();
+    let res = call.await;
+    if res.is_err() {
+        m.rpc_errors.with_label_values(&[endpoint]).inc();
+    }
+    res
+}
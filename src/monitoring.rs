@@ -1,11 +1,110 @@
-use tracing_subscriber::{fmt, EnvFilter};
+use std::time::Duration;
+
+use anyhow::Result;
+use axum::extract::State;
+use axum::http::{header, StatusCode};
+use axum::response::IntoResponse;
+use axum::routing::get;
+use axum::{Json, Router};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+use opentelemetry_sdk::Resource;
+use tracing::info;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter, Layer};
+
+fn env_filter() -> EnvFilter {
+    EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"))
+}
+
+fn json_layer<S>() -> impl Layer<S>
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+{
+    fmt::layer().json().with_current_span(true).with_span_list(true)
+}
 
 pub fn init_tracing() {
-    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
-    fmt()
-        .with_env_filter(filter)
-        .json()
-        .with_current_span(true)
-        .with_span_list(true)
+    tracing_subscriber::registry().with(env_filter()).with(json_layer()).init();
+}
+
+/// Like [`init_tracing`], and also exports spans over OTLP/HTTP to
+/// `otlp_endpoint` (e.g. a local collector on `http://127.0.0.1:4318`).
+pub fn init_daemon_tracing(otlp_endpoint: Option<&str>) -> Result<()> {
+    let otel = match otlp_endpoint {
+        Some(endpoint) => Some(tracing_opentelemetry::layer().with_tracer(otlp_tracer(endpoint)?)),
+        None => None,
+    };
+    tracing_subscriber::registry()
+        .with(env_filter())
+        .with(json_layer())
+        .with(otel)
         .init();
+    if let Some(endpoint) = otlp_endpoint {
+        info!(%endpoint, "monitoring.otlp.enabled");
+    }
+    Ok(())
+}
+
+fn otlp_tracer(endpoint: &str) -> Result<SdkTracer> {
+    let endpoint = endpoint.trim_end_matches('/');
+    let url = if endpoint.ends_with("/v1/traces") {
+        endpoint.to_string()
+    } else {
+        format!("{endpoint}/v1/traces")
+    };
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(url)
+        .with_timeout(std::time::Duration::from_secs(5))
+        .build()?;
+    let provider = SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name("solbot").build())
+        .build();
+    let tracer = provider.tracer("solbot");
+    // Keeps the provider (and its export thread) alive for the process lifetime.
+    opentelemetry::global::set_tracer_provider(provider);
+    Ok(tracer)
+}
+
+async fn metrics() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        crate::metrics::get().render(),
+    )
+}
+
+fn health_response(ok: bool, report: crate::health::Report) -> impl IntoResponse {
+    let status = if ok { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (status, Json(report))
+}
+
+/// 503 when the positions loop is stale.
+async fn healthz(State(max_tick_age): State<Duration>) -> impl IntoResponse {
+    let report = crate::health::report(max_tick_age);
+    health_response(report.live, report)
+}
+
+/// 503 when the positions loop is stale or the RPC endpoint is unreachable.
+async fn readyz(State(max_tick_age): State<Duration>) -> impl IntoResponse {
+    let report = crate::health::report(max_tick_age);
+    health_response(report.ready, report)
+}
+
+/// Serves `GET /metrics` (Prometheus), `/healthz` and `/readyz` until the listener fails.
+pub async fn serve(addr: &str, max_tick_age: Duration) -> Result<()> {
+    let app = Router::new()
+        .route("/metrics", get(metrics))
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .with_state(max_tick_age);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!(%addr, "monitoring.listening");
+    axum::serve(listener, app).await?;
+    Ok(())
 }
@@ -0,0 +1,227 @@
+use serde::{Deserialize, Serialize};
+
+use super::Severity;
+use super::Tally;
+use crate::ledger::TradeExit;
+use crate::risk::{BotMode, ClosedDay, ExitReason};
+use crate::state::ModeChange;
+
+/// Structured notifications. Slack renders them as Block Kit cards; the
+/// other sinks get [`Event::text`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Event {
+    TradeClosed(TradeClosed),
+    ModeChanged(ModeChanged),
+    DailySummary(DailySummary),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeClosed {
+    pub position_id: String,
+    pub strategy: String,
+    pub base_mint: String,
+    pub quote_mint: String,
+    pub size_usdc: f64,
+    pub entry_price: f64,
+    pub exit_price: f64,
+    pub pnl_usdc: f64,
+    pub pnl_pct: f64,
+    pub reason: ExitReason,
+    pub note: String,
+    pub buy_tx: Option<String>,
+    pub sell_tx: Option<String>,
+    /// Mode after the close was booked.
+    pub mode: BotMode,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModeChanged {
+    pub from: BotMode,
+    pub to: BotMode,
+    /// Operator name, or `risk` for automatic transitions.
+    pub by: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryPeriod {
+    #[default]
+    Day,
+    Week,
+}
+
+/// Summary of a finished trading day, or of a week for the weekly roll-up.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailySummary {
+    #[serde(default)]
+    pub period: SummaryPeriod,
+    /// `YYYY-MM-DD`, or the ISO week (`2026-W42`) for weekly roll-ups.
+    pub day_key: String,
+    pub realized_pnl_usdc: f64,
+    pub unrealized_pnl_usdc: f64,
+    pub trades: u32,
+    pub wins: u32,
+    pub start_equity_usdc: Option<f64>,
+    pub end_equity_usdc: Option<f64>,
+    pub open_positions: usize,
+    pub mode: BotMode,
+    /// Network fees of the period's exits (from the trade ledger).
+    #[serde(default)]
+    pub fees_lamports: u64,
+    #[serde(default)]
+    pub best: Option<TradeBrief>,
+    #[serde(default)]
+    pub worst: Option<TradeBrief>,
+    #[serde(default)]
+    pub mode_changes: Vec<ModeChanged>,
+    /// Alerts at warning or above.
+    #[serde(default)]
+    pub alerts: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TradeBrief {
+    pub base_mint: String,
+    pub pnl_usdc: f64,
+    pub pnl_pct: f64,
+    pub reason: ExitReason,
+}
+
+impl From<&TradeExit> for TradeBrief {
+    fn from(x: &TradeExit) -> Self {
+        Self {
+            base_mint: x.base_mint.clone(),
+            pnl_usdc: x.pnl_usdc,
+            pnl_pct: x.pnl_pct,
+            reason: x.reason,
+        }
+    }
+}
+
+impl ModeChanged {
+    /// Transition made by the risk engine (limits breached).
+    pub fn risk(from: BotMode, to: BotMode, reason: impl Into<String>) -> Self {
+        Self {
+            from,
+            to,
+            by: "risk".into(),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl From<&ModeChange> for ModeChanged {
+    fn from(c: &ModeChange) -> Self {
+        Self {
+            from: c.from,
+            to: c.to,
+            by: c.operator.clone(),
+            reason: c.reason.clone(),
+        }
+    }
+}
+
+impl DailySummary {
+    pub fn new(day: ClosedDay, open_positions: usize, mode: BotMode) -> Self {
+        Self {
+            period: SummaryPeriod::Day,
+            day_key: day.daily.day_key,
+            realized_pnl_usdc: day.daily.realized_pnl_usdc,
+            unrealized_pnl_usdc: day.daily.unrealized_pnl_usdc,
+            trades: day.daily.trades,
+            wins: day.daily.wins,
+            start_equity_usdc: day.start_equity_usdc,
+            end_equity_usdc: day.end_equity_usdc,
+            open_positions,
+            mode,
+            fees_lamports: 0,
+            best: None,
+            worst: None,
+            mode_changes: vec![],
+            alerts: 0,
+        }
+    }
+
+    /// Fills fees and best/worst trade from the period's ledger exits.
+    pub fn with_exits(mut self, exits: &[&TradeExit]) -> Self {
+        self.fees_lamports = exits.iter().filter_map(|x| x.fill.fee_lamports).sum();
+        self.best = exits.iter().max_by(|a, b| a.pnl_usdc.total_cmp(&b.pnl_usdc)).map(|x| (*x).into());
+        self.worst = exits.iter().min_by(|a, b| a.pnl_usdc.total_cmp(&b.pnl_usdc)).map(|x| (*x).into());
+        self
+    }
+
+    pub fn with_tally(mut self, tally: Tally) -> Self {
+        self.alerts = tally.alerts;
+        self.mode_changes = tally.mode_changes;
+        self
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self.period {
+            SummaryPeriod::Day => "Daily summary",
+            SummaryPeriod::Week => "Weekly summary",
+        }
+    }
+
+    pub fn equity_change_usdc(&self) -> Option<f64> {
+        Some(self.end_equity_usdc? - self.start_equity_usdc?)
+    }
+}
+
+impl Event {
+    pub fn severity(&self) -> Severity {
+        match self {
+            Self::TradeClosed(_) | Self::DailySummary(_) => Severity::Info,
+            Self::ModeChanged(m) => match m.to {
+                BotMode::Trading => Severity::Info,
+                BotMode::ReadOnly => Severity::Warning,
+                BotMode::EmergencyStop => Severity::Critical,
+            },
+        }
+    }
+
+    /// Plain-text rendering for sinks without rich formatting.
+    pub fn text(&self) -> String {
+        match self {
+            Self::TradeClosed(t) => format!(
+                "[SIE] SELL {} reason={} pnl=${:.2} ({:.2}%) tx={} mode={:?}",
+                t.base_mint,
+                t.reason,
+                t.pnl_usdc,
+                t.pnl_pct * 100.0,
+                t.sell_tx.as_deref().unwrap_or("-"),
+                t.mode
+            ),
+            Self::ModeChanged(m) => format!("[SIE] MODE {:?} -> {:?} by {}: {}", m.from, m.to, m.by, m.reason),
+            Self::DailySummary(d) => {
+                let equity = match d.equity_change_usdc() {
+                    Some(chg) => format!(" equity_change=${chg:.2}"),
+                    None => String::new(),
+                };
+                let tag = match d.period {
+                    SummaryPeriod::Day => "DAILY",
+                    SummaryPeriod::Week => "WEEKLY",
+                };
+                let best = match (&d.best, &d.worst) {
+                    (Some(b), Some(w)) => format!(" best=${:.2} worst=${:.2}", b.pnl_usdc, w.pnl_usdc),
+                    _ => String::new(),
+                };
+                format!(
+                    "[SIE] {tag} {} realized=${:.2} trades={} wins={}{}{best} fees={} lamports mode_changes={} alerts={} open={} mode={:?}",
+                    d.day_key,
+                    d.realized_pnl_usdc,
+                    d.trades,
+                    d.wins,
+                    equity,
+                    d.fees_lamports,
+                    d.mode_changes.len(),
+                    d.alerts,
+                    d.open_positions,
+                    d.mode
+                )
+            }
+        }
+    }
+}
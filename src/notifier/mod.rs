@@ -0,0 +1,313 @@
+pub mod events;
+mod outbox;
+pub mod sinks;
+mod throttle;
+
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+use crate::config::Config;
+pub use events::Event;
+use events::ModeChanged;
+use outbox::{FailedLine, Outbox, Pending};
+use sinks::{DiscordSink, Sink, SlackSink, TelegramSink, WebhookSink};
+use throttle::Throttle;
+
+/// Alert severity. Each sink only receives alerts at or above its minimum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl Severity {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.to_lowercase().as_str() {
+            "info" => Some(Self::Info),
+            "warn" | "warning" => Some(Self::Warning),
+            "crit" | "critical" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Critical => "critical",
+        })
+    }
+}
+
+/// One alert. Alerts sharing a `key` are deduplicated: repeats are folded
+/// into periodic digests instead of being sent one by one.
+#[derive(Debug, Clone)]
+pub struct Alert {
+    pub severity: Severity,
+    pub key: String,
+    pub text: String,
+}
+
+/// What the notifier saw since the last [`Notifier::take_tally`]: alerts at
+/// warning or above (suppressed repeats not counted), and mode changes. Feeds the daily/weekly summaries.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Tally {
+    pub alerts: u32,
+    pub mode_changes: Vec<ModeChanged>,
+}
+
+impl Tally {
+    pub fn absorb(&mut self, other: Tally) {
+        self.alerts += other.alerts;
+        self.mode_changes.extend(other.mode_changes);
+    }
+}
+
+/// Delivery settings for the outbox worker.
+#[derive(Debug, Clone)]
+pub struct DeliveryOptions {
+    pub outbox_path: String,
+    pub failed_log_path: String,
+    /// Attempts per sink before an alert is dropped to the fallback log.
+    pub max_attempts: u32,
+}
+
+/// Fans alerts out to every configured sink (Slack, Telegram, Discord,
+/// generic webhook) whose minimum severity they meet.
+///
+/// Alerts are queued in an on-disk outbox and delivered by a background
+/// worker ([`Notifier::start`]), retrying failed sinks with exponential
+/// backoff. Callers never wait on the network and cannot lose an alert to a
+/// sink outage; what still fails after `max_attempts` is written to the
+/// fallback log.
+#[derive(Clone)]
+pub struct Notifier {
+    sinks: Arc<Vec<Box<dyn Sink>>>,
+    throttle: Arc<Mutex<Throttle>>,
+    outbox: Arc<Mutex<Outbox>>,
+    wake: Arc<Notify>,
+    opts: Arc<DeliveryOptions>,
+    tally: Arc<Mutex<Tally>>,
+}
+
+impl Notifier {
+    pub fn new(
+        sinks: Vec<Box<dyn Sink>>,
+        suppress: Duration,
+        escalate_after: Option<Duration>,
+        opts: DeliveryOptions,
+    ) -> Self {
+        Self {
+            sinks: Arc::new(sinks),
+            throttle: Arc::new(Mutex::new(Throttle::new(suppress, escalate_after))),
+            outbox: Arc::new(Mutex::new(Outbox::new(&opts.outbox_path))),
+            wake: Arc::new(Notify::new()),
+            opts: Arc::new(opts),
+            tally: Arc::new(Mutex::new(Tally::default())),
+        }
+    }
+
+    pub fn from_config(cfg: &Config) -> Self {
+        let mut sinks: Vec<Box<dyn Sink>> = vec![];
+        if let Some(url) = &cfg.slack_webhook_url {
+            sinks.push(Box::new(SlackSink::new(
+                url.clone(),
+                cfg.slack_min_severity,
+                cfg.explorer_tx_url.clone(),
+            )));
+        }
+        if let (Some(token), Some(chat_id)) = (&cfg.telegram_bot_token, &cfg.telegram_chat_id) {
+            sinks.push(Box::new(TelegramSink::new(
+                token.clone(),
+                chat_id.clone(),
+                cfg.telegram_min_severity,
+            )));
+        }
+        if let Some(url) = &cfg.discord_webhook_url {
+            sinks.push(Box::new(DiscordSink::new(url.clone(), cfg.discord_min_severity)));
+        }
+        if let Some(url) = &cfg.alert_webhook_url {
+            sinks.push(Box::new(WebhookSink::new(url.clone(), cfg.alert_webhook_min_severity)));
+        }
+        Self::new(
+            sinks,
+            Duration::from_secs(cfg.alert_suppress_secs),
+            (cfg.alert_escalate_secs > 0).then(|| Duration::from_secs(cfg.alert_escalate_secs)),
+            DeliveryOptions {
+                outbox_path: cfg.alert_outbox_path.clone(),
+                failed_log_path: cfg.alert_failed_log_path.clone(),
+                max_attempts: cfg.alert_max_attempts,
+            },
+        )
+    }
+
+    /// Names of the configured sinks (for the boot log).
+    pub fn sink_names(&self) -> Vec<&'static str> {
+        self.sinks.iter().map(|s| s.name()).collect()
+    }
+
+    /// Alert deduplicated on its exact text.
+    pub fn alert(&self, severity: Severity, text: &str) {
+        self.alert_keyed(severity, text, text)
+    }
+
+    /// Alert deduplicated on `key`, for messages whose text varies
+    /// (error details, amounts) while the condition is the same.
+    pub fn alert_keyed(&self, severity: Severity, key: &str, text: &str) {
+        let alert = Alert {
+            severity,
+            key: key.to_string(),
+            text: text.to_string(),
+        };
+        let admitted = self.throttle.lock().expect("throttle lock").admit(alert, Instant::now());
+        if let Some(a) = admitted {
+            if a.severity >= Severity::Warning {
+                self.tally.lock().expect("tally lock").alerts += 1;
+            }
+            self.enqueue(a.severity, &a.text, None);
+        }
+    }
+
+    /// Structured notification (trade, mode change, daily summary). Not
+    /// deduplicated: each event is a distinct fact.
+    pub fn event(&self, event: Event) {
+        if let Event::ModeChanged(m) = &event {
+            self.tally.lock().expect("tally lock").mode_changes.push(m.clone());
+        }
+        self.enqueue(event.severity(), &event.text(), Some(event));
+    }
+
+    /// Alerts and mode changes since the last call.
+    pub fn take_tally(&self) -> Tally {
+        std::mem::take(&mut *self.tally.lock().expect("tally lock"))
+    }
+
+    /// Queues digests for suppression windows that have closed. Call periodically.
+    pub fn flush_digests(&self) {
+        let due = self.throttle.lock().expect("throttle lock").due(Instant::now());
+        for a in due {
+            self.enqueue(a.severity, &a.text, None);
+        }
+    }
+
+    fn enqueue(&self, severity: Severity, text: &str, event: Option<Event>) {
+        // In dev/test we allow no sinks at all.
+        let sinks: Vec<String> = self
+            .sinks
+            .iter()
+            .filter(|s| severity >= s.min_severity())
+            .map(|s| s.name().to_string())
+            .collect();
+        if sinks.is_empty() {
+            return;
+        }
+        if let Err(e) = self.outbox.lock().expect("outbox lock").push(severity, text, event, sinks) {
+            // Still queued in memory; only restart-survival is lost.
+            error!(error = %e, "notifier.outbox.write_failed");
+        }
+        self.wake.notify_one();
+    }
+
+    /// Loads the on-disk outbox and spawns the delivery worker.
+    pub fn start(&self) -> Result<JoinHandle<()>> {
+        let restored = self.outbox.lock().expect("outbox lock").open()?;
+        if restored > 0 {
+            info!(restored, path = %self.opts.outbox_path, "notifier.outbox.restored");
+        }
+        let worker = self.clone();
+        Ok(tokio::spawn(async move { worker.run().await }))
+    }
+
+    async fn run(self) {
+        loop {
+            let due = self.outbox.lock().expect("outbox lock").due(outbox::now_ms());
+            for p in due {
+                let id = p.id;
+                let updated = self.attempt(p).await;
+                if let Err(e) = self.outbox.lock().expect("outbox lock").update(id, updated) {
+                    error!(error = %e, "notifier.outbox.write_failed");
+                }
+            }
+
+            let wait_ms = self
+                .outbox
+                .lock()
+                .expect("outbox lock")
+                .next_wakeup_ms(outbox::now_ms())
+                .unwrap_or(60_000);
+            tokio::select! {
+                _ = self.wake.notified() => {}
+                _ = tokio::time::sleep(Duration::from_millis(wait_ms as u64)) => {}
+            }
+        }
+    }
+
+    /// One delivery attempt to every sink still owed `p`. Returns the entry
+    /// to keep for a retry, or `None` when it is done (delivered or dropped).
+    async fn attempt(&self, mut p: Pending) -> Option<Pending> {
+        let mut remaining = vec![];
+        let mut errors = vec![];
+        for name in &p.sinks {
+            let Some(sink) = self.sinks.iter().find(|s| s.name() == name) else {
+                // Sink removed from the config since the alert was queued.
+                warn!(sink = %name, id = p.id, "notifier.sink_gone");
+                continue;
+            };
+            if let Err(e) = sink.send(p.severity, &p.text, p.event.as_ref()).await {
+                warn!(sink = %name, id = p.id, attempt = p.attempts + 1, error = %e, "notifier.sink_failed");
+                errors.push(format!("{name}: {e}"));
+                remaining.push(name.clone());
+            }
+        }
+
+        if remaining.is_empty() {
+            if p.attempts > 0 {
+                info!(id = p.id, attempts = p.attempts + 1, "notifier.delivered_after_retry");
+            }
+            return None;
+        }
+
+        p.attempts += 1;
+        p.sinks = remaining;
+        p.last_error = Some(errors.join("; "));
+        if p.attempts == 1 {
+            self.log_failed("undelivered", &p);
+        }
+        if p.attempts >= self.opts.max_attempts {
+            error!(id = p.id, attempts = p.attempts, sinks = ?p.sinks, "notifier.alert_dropped");
+            self.log_failed("dropped", &p);
+            return None;
+        }
+        p.next_attempt_ms = outbox::now_ms() + outbox::backoff(p.attempts).as_millis() as i64;
+        Some(p)
+    }
+
+    fn log_failed(&self, status: &'static str, p: &Pending) {
+        let line = FailedLine {
+            status,
+            ts: &p.ts,
+            severity: p.severity,
+            text: &p.text,
+            sinks: &p.sinks,
+            attempts: p.attempts,
+            error: p.last_error.as_deref().unwrap_or_default(),
+        };
+        let res = serde_json::to_string(&line)
+            .map_err(anyhow::Error::from)
+            .and_then(|l| crate::logger::append_line(&self.opts.failed_log_path, &l));
+        if let Err(e) = res {
+            error!(error = %e, path = %self.opts.failed_log_path, "notifier.failed_log.write_failed");
+        }
+    }
+}
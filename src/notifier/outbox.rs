@@ -0,0 +1,209 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{Event, Severity};
+
+/// First retry delay; doubles per failed attempt up to `MAX_BACKOFF`.
+const BASE_BACKOFF: Duration = Duration::from_secs(2);
+const MAX_BACKOFF: Duration = Duration::from_secs(600);
+
+/// An alert not yet delivered to every sink it is meant for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Pending {
+    pub id: u64,
+    /// RFC 3339, when the alert was raised.
+    pub ts: String,
+    pub severity: Severity,
+    pub text: String,
+    /// Sinks still owed this alert.
+    pub sinks: Vec<String>,
+    pub attempts: u32,
+    /// Unix millis of the next attempt.
+    pub next_attempt_ms: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+    /// Structured form, for sinks that render rich messages.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event: Option<Event>,
+}
+
+/// One line of the fallback log.
+#[derive(Debug, Serialize)]
+pub struct FailedLine<'a> {
+    /// `undelivered` on the first failure, `dropped` when retries are exhausted.
+    pub status: &'static str,
+    pub ts: &'a str,
+    pub severity: Severity,
+    pub text: &'a str,
+    pub sinks: &'a [String],
+    pub attempts: u32,
+    pub error: &'a str,
+}
+
+pub fn now_ms() -> i64 {
+    chrono::Utc::now().timestamp_millis()
+}
+
+pub fn backoff(attempts: u32) -> Duration {
+    BASE_BACKOFF
+        .checked_mul(1 << attempts.saturating_sub(1).min(16))
+        .map_or(MAX_BACKOFF, |d| d.min(MAX_BACKOFF))
+}
+
+/// Undelivered alerts, mirrored to a JSON file so they survive a restart.
+pub struct Outbox {
+    path: PathBuf,
+    /// Nothing is written until the file on disk has been loaded.
+    opened: bool,
+    next_id: u64,
+    entries: Vec<Pending>,
+}
+
+impl Outbox {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            opened: false,
+            next_id: 1,
+            entries: vec![],
+        }
+    }
+
+    /// Loads alerts left over from a previous run, ahead of anything queued since boot.
+    pub fn open(&mut self) -> Result<usize> {
+        let mut restored: Vec<Pending> = if self.path.exists() {
+            let raw = fs::read_to_string(&self.path)?;
+            serde_json::from_str(&raw).map_err(|e| {
+                anyhow!("alert outbox {} is unreadable ({e}); move it aside to start", self.path.display())
+            })?
+        } else {
+            vec![]
+        };
+        let n = restored.len();
+        // A restart is a good moment to retry; don't wait out the old backoff.
+        let now = now_ms();
+        for p in &mut restored {
+            p.next_attempt_ms = now;
+        }
+
+        self.next_id = restored.iter().map(|p| p.id).max().unwrap_or(0) + 1;
+        for p in &mut self.entries {
+            p.id = self.next_id;
+            self.next_id += 1;
+        }
+        restored.append(&mut self.entries);
+        self.entries = restored;
+        self.opened = true;
+        self.save()?;
+        Ok(n)
+    }
+
+    pub fn push(&mut self, severity: Severity, text: &str, event: Option<Event>, sinks: Vec<String>) -> Result<()> {
+        self.entries.push(Pending {
+            id: self.next_id,
+            ts: chrono::Utc::now().to_rfc3339(),
+            severity,
+            text: text.to_string(),
+            sinks,
+            attempts: 0,
+            next_attempt_ms: now_ms(),
+            last_error: None,
+            event,
+        });
+        self.next_id += 1;
+        self.save()
+    }
+
+    /// Alerts whose next attempt is due, oldest first.
+    pub fn due(&self, now_ms: i64) -> Vec<Pending> {
+        self.entries.iter().filter(|p| p.next_attempt_ms <= now_ms).cloned().collect()
+    }
+
+    /// Milliseconds until the earliest scheduled attempt.
+    pub fn next_wakeup_ms(&self, now_ms: i64) -> Option<i64> {
+        self.entries.iter().map(|p| (p.next_attempt_ms - now_ms).max(0)).min()
+    }
+
+    /// Replaces an entry with its post-attempt state, or removes it.
+    pub fn update(&mut self, id: u64, updated: Option<Pending>) -> Result<()> {
+        match updated {
+            Some(p) => {
+                if let Some(e) = self.entries.iter_mut().find(|e| e.id == id) {
+                    *e = p;
+                }
+            }
+            None => self.entries.retain(|e| e.id != id),
+        }
+        self.save()
+    }
+
+    fn save(&self) -> Result<()> {
+        if !self.opened {
+            return Ok(());
+        }
+        if self.entries.is_empty() && !self.path.exists() {
+            return Ok(());
+        }
+        write_atomic(&self.path, &serde_json::to_string_pretty(&self.entries)?)
+    }
+}
+
+/// Write-then-rename so a crash never leaves a truncated outbox.
+fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("solbot-outbox-{}-{name}.json", std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_cap() {
+        assert_eq!(backoff(0), BASE_BACKOFF);
+        assert_eq!(backoff(1), BASE_BACKOFF);
+        assert_eq!(backoff(2), BASE_BACKOFF * 2);
+        assert_eq!(backoff(5), BASE_BACKOFF * 16);
+        assert_eq!(backoff(9), BASE_BACKOFF * 256);
+        assert_eq!(backoff(10), MAX_BACKOFF);
+        assert_eq!(backoff(u32::MAX), MAX_BACKOFF);
+    }
+
+    #[test]
+    fn open_restores_the_file_and_renumbers_alerts_queued_before_it() {
+        let path = scratch("restore");
+        let mut old = Outbox::new(&path);
+        old.open().unwrap();
+        for text in ["a", "b", "c"] {
+            old.push(Severity::Warning, text, None, vec!["slack".into()]).unwrap();
+        }
+        old.update(1, None).unwrap();
+
+        // Next run: two alerts are raised before the outbox file is loaded.
+        let mut outbox = Outbox::new(&path);
+        outbox.push(Severity::Critical, "early 1", None, vec!["slack".into()]).unwrap();
+        outbox.push(Severity::Critical, "early 2", None, vec!["slack".into()]).unwrap();
+        assert_eq!(outbox.open().unwrap(), 2);
+
+        let all = outbox.due(i64::MAX);
+        let got: Vec<(u64, &str)> = all.iter().map(|p| (p.id, p.text.as_str())).collect();
+        assert_eq!(got, vec![(2, "b"), (3, "c"), (4, "early 1"), (5, "early 2")]);
+
+        outbox.push(Severity::Info, "later", None, vec!["slack".into()]).unwrap();
+        assert_eq!(outbox.due(i64::MAX).last().unwrap().id, 6);
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,386 @@
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use super::events::{DailySummary, ModeChanged, TradeBrief, TradeClosed};
+use super::{Event, Severity};
+use crate::secret::Secret;
+
+/// One alert destination.
+#[async_trait]
+pub trait Sink: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn min_severity(&self) -> Severity;
+    /// `text` is always set; `event` is the structured form when there is one.
+    async fn send(&self, severity: Severity, text: &str, event: Option<&Event>) -> Result<()>;
+}
+
+/// Sinks are called one after another by the delivery worker, so a hung
+/// endpoint must not stall the queue.
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn http_client() -> Client {
+    Client::builder().timeout(SEND_TIMEOUT).build().unwrap_or_default()
+}
+
+async fn post_json<T: Serialize + Sync>(http: &Client, name: &str, url: &str, body: &T) -> Result<()> {
+    // `without_url`: webhook URLs carry their credentials.
+    let resp = http
+        .post(url)
+        .json(body)
+        .send()
+        .await
+        .map_err(|e| anyhow!("{name} request failed: {}", e.without_url()))?;
+    if !resp.status().is_success() {
+        return Err(anyhow!("{name} returned {}", resp.status()));
+    }
+    Ok(())
+}
+
+// --- Slack incoming webhook ---
+
+pub struct SlackSink {
+    url: Secret,
+    min: Severity,
+    /// Transaction link template, `{sig}` is replaced by the signature.
+    explorer_tx_url: String,
+    http: Client,
+}
+
+#[derive(Serialize)]
+struct SlackPayload<'a> {
+    /// Notification / fallback text; `blocks` is what the channel shows.
+    text: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    blocks: Option<Vec<Value>>,
+}
+
+impl SlackSink {
+    pub fn new(url: Secret, min: Severity, explorer_tx_url: String) -> Self {
+        Self {
+            url,
+            min,
+            explorer_tx_url,
+            http: http_client(),
+        }
+    }
+
+    fn tx_link(&self, sig: Option<&str>) -> String {
+        match sig.filter(|s| !s.is_empty()) {
+            Some(sig) => format!(
+                "<{}|{}>",
+                self.explorer_tx_url.replace("{sig}", sig),
+                short(sig)
+            ),
+            None => "-".into(),
+        }
+    }
+
+    fn blocks(&self, event: &Event) -> Vec<Value> {
+        match event {
+            Event::TradeClosed(t) => self.trade_closed(t),
+            Event::ModeChanged(m) => mode_changed(m),
+            Event::DailySummary(d) => daily_summary(d),
+        }
+    }
+
+    fn trade_closed(&self, t: &TradeClosed) -> Vec<Value> {
+        let icon = if t.pnl_usdc >= 0.0 { ":large_green_circle:" } else { ":red_circle:" };
+        let mut blocks = vec![
+            header(&format!("{icon} SELL {} · {}", short(&t.base_mint), t.reason)),
+            fields(&[
+                ("Mint", format!("`{}`", t.base_mint)),
+                ("Size", usd(t.size_usdc)),
+                ("Entry", price(t.entry_price)),
+                ("Exit", price(t.exit_price)),
+                ("PnL", format!("{} ({})", usd(t.pnl_usdc), pct(t.pnl_pct))),
+                ("Reason", t.reason.to_string()),
+            ]),
+        ];
+        if !t.note.is_empty() {
+            blocks.push(section(&format!("_{}_", t.note)));
+        }
+        blocks.push(context(&format!(
+            "position `{}` · buy {} · sell {} · mode {:?}",
+            t.position_id,
+            self.tx_link(t.buy_tx.as_deref()),
+            self.tx_link(t.sell_tx.as_deref()),
+            t.mode
+        )));
+        blocks
+    }
+}
+
+fn mode_changed(m: &ModeChanged) -> Vec<Value> {
+    let icon = match m.to {
+        crate::risk::BotMode::Trading => ":arrow_forward:",
+        crate::risk::BotMode::ReadOnly => ":pause_button:",
+        crate::risk::BotMode::EmergencyStop => ":rotating_light:",
+    };
+    vec![
+        header(&format!("{icon} {:?} → {:?}", m.from, m.to)),
+        section(&m.reason),
+        context(&format!("by {}", m.by)),
+    ]
+}
+
+fn daily_summary(d: &DailySummary) -> Vec<Value> {
+    let win_rate = if d.trades > 0 {
+        format!("{}/{} ({:.0}%)", d.wins, d.trades, d.wins as f64 / d.trades as f64 * 100.0)
+    } else {
+        "no trades".into()
+    };
+    let equity = match (d.start_equity_usdc, d.end_equity_usdc) {
+        (Some(a), Some(b)) => format!("{} → {} ({})", usd(a), usd(b), usd(b - a)),
+        (_, Some(b)) => usd(b),
+        _ => "-".into(),
+    };
+    let mut blocks = vec![
+        header(&format!(":bar_chart: {} {}", d.title(), d.day_key)),
+        fields(&[
+            ("Realized PnL", usd(d.realized_pnl_usdc)),
+            ("Unrealized PnL", usd(d.unrealized_pnl_usdc)),
+            ("Wins", win_rate),
+            ("Equity", equity),
+            ("Fees", format!("{} lamports", d.fees_lamports)),
+            ("Alerts", d.alerts.to_string()),
+            ("Open positions", d.open_positions.to_string()),
+            ("Mode", format!("{:?}", d.mode)),
+        ]),
+    ];
+    let brief = |t: &TradeBrief| format!("`{}` {} ({})", short(&t.base_mint), usd(t.pnl_usdc), t.reason);
+    if let (Some(b), Some(w)) = (&d.best, &d.worst) {
+        blocks.push(section(&format!("*Best* {}\n*Worst* {}", brief(b), brief(w))));
+    }
+    if !d.mode_changes.is_empty() {
+        let changes: Vec<String> = d
+            .mode_changes
+            .iter()
+            .map(|m| format!("{:?} → {:?} ({})", m.from, m.to, m.by))
+            .collect();
+        blocks.push(context(&format!("Mode changes: {}", changes.join(", "))));
+    }
+    blocks
+}
+
+// Block Kit helpers.
+
+fn header(text: &str) -> Value {
+    json!({ "type": "header", "text": { "type": "plain_text", "text": text, "emoji": true } })
+}
+
+fn section(markdown: &str) -> Value {
+    json!({ "type": "section", "text": { "type": "mrkdwn", "text": markdown } })
+}
+
+fn fields(pairs: &[(&str, String)]) -> Value {
+    let fields: Vec<Value> = pairs
+        .iter()
+        .map(|(k, v)| json!({ "type": "mrkdwn", "text": format!("*{k}*\n{v}") }))
+        .collect();
+    json!({ "type": "section", "fields": fields })
+}
+
+fn context(markdown: &str) -> Value {
+    json!({ "type": "context", "elements": [{ "type": "mrkdwn", "text": markdown }] })
+}
+
+/// `AbCd…WxYz` for mints and signatures.
+fn short(s: &str) -> String {
+    match (s.get(..4), s.get(s.len().saturating_sub(4)..)) {
+        (Some(head), Some(tail)) if s.len() > 12 => format!("{head}…{tail}"),
+        _ => s.to_string(),
+    }
+}
+
+fn usd(v: f64) -> String {
+    // abs() also folds -0.0 (and rounding to zero) into "$0.00".
+    if v <= -0.005 {
+        format!("-${:.2}", -v)
+    } else {
+        format!("${:.2}", v.abs())
+    }
+}
+
+fn pct(v: f64) -> String {
+    format!("{:+.2}%", v * 100.0)
+}
+
+fn price(v: f64) -> String {
+    format!("{v:.6}")
+}
+
+#[async_trait]
+impl Sink for SlackSink {
+    fn name(&self) -> &'static str {
+        "slack"
+    }
+
+    fn min_severity(&self) -> Severity {
+        self.min
+    }
+
+    async fn send(&self, _severity: Severity, text: &str, event: Option<&Event>) -> Result<()> {
+        let payload = SlackPayload {
+            text,
+            blocks: event.map(|e| self.blocks(e)),
+        };
+        post_json(&self.http, "slack webhook", self.url.expose(), &payload).await
+    }
+}
+
+// --- Telegram bot API ---
+
+pub struct TelegramSink {
+    bot_token: Secret,
+    chat_id: String,
+    min: Severity,
+    http: Client,
+}
+
+#[derive(Serialize)]
+struct TelegramMessage<'a> {
+    chat_id: &'a str,
+    text: &'a str,
+    disable_web_page_preview: bool,
+}
+
+impl TelegramSink {
+    pub fn new(bot_token: Secret, chat_id: String, min: Severity) -> Self {
+        Self {
+            bot_token,
+            chat_id,
+            min,
+            http: http_client(),
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for TelegramSink {
+    fn name(&self) -> &'static str {
+        "telegram"
+    }
+
+    fn min_severity(&self) -> Severity {
+        self.min
+    }
+
+    async fn send(&self, _severity: Severity, text: &str, _event: Option<&Event>) -> Result<()> {
+        let url = format!("https://api.telegram.org/bot{}/sendMessage", self.bot_token.expose());
+        post_json(
+            &self.http,
+            "telegram sendMessage",
+            &url,
+            &TelegramMessage {
+                chat_id: &self.chat_id,
+                text,
+                disable_web_page_preview: true,
+            },
+        )
+        .await
+    }
+}
+
+// --- Discord webhook ---
+
+/// Discord rejects message content above 2000 chars.
+const DISCORD_MAX_CONTENT: usize = 2000;
+
+pub struct DiscordSink {
+    url: Secret,
+    min: Severity,
+    http: Client,
+}
+
+#[derive(Serialize)]
+struct DiscordPayload<'a> {
+    content: &'a str,
+}
+
+impl DiscordSink {
+    pub fn new(url: Secret, min: Severity) -> Self {
+        Self {
+            url,
+            min,
+            http: http_client(),
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for DiscordSink {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    fn min_severity(&self) -> Severity {
+        self.min
+    }
+
+    async fn send(&self, _severity: Severity, text: &str, _event: Option<&Event>) -> Result<()> {
+        let content = match text.char_indices().nth(DISCORD_MAX_CONTENT) {
+            Some((i, _)) => &text[..i],
+            None => text,
+        };
+        post_json(&self.http, "discord webhook", self.url.expose(), &DiscordPayload { content }).await
+    }
+}
+
+// --- generic JSON webhook ---
+
+pub struct WebhookSink {
+    url: Secret,
+    min: Severity,
+    http: Client,
+}
+
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    source: &'static str,
+    ts: String,
+    severity: Severity,
+    text: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    event: Option<&'a Event>,
+}
+
+impl WebhookSink {
+    pub fn new(url: Secret, min: Severity) -> Self {
+        Self {
+            url,
+            min,
+            http: http_client(),
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for WebhookSink {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn min_severity(&self) -> Severity {
+        self.min
+    }
+
+    async fn send(&self, severity: Severity, text: &str, event: Option<&Event>) -> Result<()> {
+        post_json(
+            &self.http,
+            "alert webhook",
+            self.url.expose(),
+            &WebhookPayload {
+                source: "solbot",
+                ts: chrono::Utc::now().to_rfc3339(),
+                severity,
+                text,
+                event,
+            },
+        )
+        .await
+    }
+}
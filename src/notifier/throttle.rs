@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use super::{Alert, Severity};
+
+/// Per-key alert suppression.
+///
+/// The first alert for a key goes out immediately. Repeats within the
+/// suppression window are counted, and one digest is sent when the window
+/// closes ("… ×42 in last 10m"). If a key keeps firing for longer than
+/// `escalate_after`, its next digest is raised one severity level (once).
+pub struct Throttle {
+    window: Duration,
+    escalate_after: Option<Duration>,
+    entries: HashMap<String, Entry>,
+}
+
+struct Entry {
+    severity: Severity,
+    text: String,
+    /// Start of the current uninterrupted run of this key.
+    first_seen: Instant,
+    window_start: Instant,
+    suppressed: u32,
+    escalated: bool,
+}
+
+fn fmt_duration(d: Duration) -> String {
+    let s = d.as_secs();
+    match s {
+        s if s >= 3600 && s % 3600 == 0 => format!("{}h", s / 3600),
+        s if s >= 60 => format!("{}m", s / 60),
+        s => format!("{s}s"),
+    }
+}
+
+fn raise(s: Severity) -> Severity {
+    match s {
+        Severity::Info => Severity::Warning,
+        Severity::Warning | Severity::Critical => Severity::Critical,
+    }
+}
+
+impl Throttle {
+    pub fn new(window: Duration, escalate_after: Option<Duration>) -> Self {
+        Self {
+            window,
+            escalate_after,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Returns the alert to send now, if any.
+    pub fn admit(&mut self, alert: Alert, now: Instant) -> Option<Alert> {
+        // A closed window is digested (or forgotten) first; an entry left
+        // after that is inside its window, so this occurrence is suppressed.
+        let digest = self.digest_if_due(&alert.key, now);
+
+        if let Some(e) = self.entries.get_mut(&alert.key) {
+            e.suppressed += 1;
+            e.severity = e.severity.max(alert.severity);
+            e.text = alert.text;
+            return digest;
+        }
+
+        self.entries.insert(
+            alert.key.clone(),
+            Entry {
+                severity: alert.severity,
+                text: alert.text.clone(),
+                first_seen: now,
+                window_start: now,
+                suppressed: 0,
+                escalated: false,
+            },
+        );
+        Some(alert)
+    }
+
+    /// Digests for every key whose window has closed; quiet keys are forgotten.
+    pub fn due(&mut self, now: Instant) -> Vec<Alert> {
+        let keys: Vec<String> = self.entries.keys().cloned().collect();
+        keys.iter().filter_map(|k| self.digest_if_due(k, now)).collect()
+    }
+
+    fn digest_if_due(&mut self, key: &str, now: Instant) -> Option<Alert> {
+        let e = self.entries.get_mut(key)?;
+        if now.duration_since(e.window_start) < self.window {
+            return None;
+        }
+        if e.suppressed == 0 {
+            // Nothing repeated in a full window: the condition cleared.
+            self.entries.remove(key);
+            return None;
+        }
+
+        let mut severity = e.severity;
+        let mut text = format!(
+            "{} ×{} in last {}",
+            e.text,
+            e.suppressed,
+            fmt_duration(now.duration_since(e.window_start))
+        );
+        let running = now.duration_since(e.first_seen);
+        if let Some(after) = self.escalate_after {
+            if !e.escalated && running >= after {
+                e.escalated = true;
+                severity = raise(severity);
+                text = format!("[ESCALATED] ongoing for {}: {text}", fmt_duration(running));
+            }
+        }
+
+        e.window_start = now;
+        e.suppressed = 0;
+        Some(Alert {
+            severity,
+            key: key.to_string(),
+            text,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alert(severity: Severity, text: &str) -> Alert {
+        Alert {
+            severity,
+            key: "rpc".into(),
+            text: text.into(),
+        }
+    }
+
+    const MIN: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn repeats_inside_the_window_are_suppressed_and_digested_when_it_closes() {
+        let mut t = Throttle::new(10 * MIN, None);
+        let t0 = Instant::now();
+
+        let first = t.admit(alert(Severity::Warning, "rpc down"), t0).unwrap();
+        assert_eq!(first.text, "rpc down");
+        for i in 1..=3 {
+            assert!(t.admit(alert(Severity::Warning, "rpc still down"), t0 + i * MIN).is_none());
+        }
+        assert!(t.due(t0 + 9 * MIN).is_empty());
+
+        let digest = t.due(t0 + 10 * MIN);
+        assert_eq!(digest.len(), 1);
+        assert_eq!(digest[0].severity, Severity::Warning);
+        assert_eq!(digest[0].text, "rpc still down ×3 in last 10m");
+
+        // A quiet window forgets the key; the next alert goes out at once.
+        assert!(t.due(t0 + 20 * MIN).is_empty());
+        assert!(t.admit(alert(Severity::Warning, "rpc down"), t0 + 21 * MIN).is_some());
+    }
+
+    #[test]
+    fn a_key_that_keeps_firing_escalates_once() {
+        let mut t = Throttle::new(10 * MIN, Some(15 * MIN));
+        let t0 = Instant::now();
+        t.admit(alert(Severity::Warning, "rpc down"), t0);
+
+        let mut digests = vec![];
+        for m in 1..40 {
+            let now = t0 + m * MIN;
+            digests.extend(t.admit(alert(Severity::Warning, "rpc down"), now));
+        }
+        assert_eq!(digests.len(), 3);
+        assert_eq!(digests[0].severity, Severity::Warning);
+        assert_eq!(digests[1].severity, Severity::Critical);
+        assert!(digests[1].text.starts_with("[ESCALATED] ongoing for 20m: rpc down ×"));
+        assert_eq!(digests[2].severity, Severity::Warning);
+        assert!(!digests[2].text.contains("ESCALATED"));
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use anyhow::Result;
+use tokio::signal::unix::Signal;
+use tokio::sync::watch;
+use tracing::{info, warn};
+
+use crate::config::{env_nonempty, Config, MomentumConfig};
+use crate::notifier::{Notifier, Severity};
+use crate::risk::RiskParams;
+
+/// Parameters the daemon applies without a restart.
+///
+/// Open positions keep the exit rules snapshotted at entry
+/// (`Position::stop_loss_pct` etc.); only new decisions use these.
+#[derive(Debug, Clone)]
+pub struct LiveParams {
+    pub risk: RiskParams,
+    pub force_reduce_on_daily_loss: bool,
+    pub momentum: MomentumConfig,
+}
+
+/// Copies one hot key from the new config into the running one.
+type ApplyHot = fn(&mut Config, &Config);
+
+/// Config keys covered by [`LiveParams`], each with how to apply it to the
+/// running config; any other change needs a restart.
+const HOT_KEYS: &[(&str, ApplyHot)] = &[
+    ("capital_usdc", |c, n| c.capital_usdc = n.capital_usdc),
+    ("position_size_usdc", |c, n| c.position_size_usdc = n.position_size_usdc),
+    ("max_open_positions", |c, n| c.max_open_positions = n.max_open_positions),
+    ("max_daily_loss_pct", |c, n| c.max_daily_loss_pct = n.max_daily_loss_pct),
+    ("stop_loss_pct", |c, n| c.stop_loss_pct = n.stop_loss_pct),
+    ("take_profit_pct", |c, n| c.take_profit_pct = n.take_profit_pct),
+    ("trailing_arm_pct", |c, n| c.trailing_arm_pct = n.trailing_arm_pct),
+    ("portfolio_hard_stop_pct", |c, n| c.portfolio_hard_stop_pct = n.portfolio_hard_stop_pct),
+    ("force_reduce_on_daily_loss", |c, n| c.force_reduce_on_daily_loss = n.force_reduce_on_daily_loss),
+    ("strategies", |c, n| c.strategies = n.strategies.clone()),
+];
+
+fn is_hot(key: &str) -> bool {
+    HOT_KEYS.iter().any(|(k, _)| *k == key)
+}
+
+impl LiveParams {
+    pub fn from_config(cfg: &Config) -> Self {
+        Self {
+            risk: RiskParams {
+                capital_usdc: cfg.capital_usdc,
+                position_size_usdc: cfg.position_size_usdc,
+                max_open_positions: cfg.max_open_positions,
+                max_daily_loss_pct: cfg.max_daily_loss_pct,
+                stop_loss_pct: cfg.stop_loss_pct,
+                take_profit_pct: cfg.take_profit_pct,
+                trailing_arm_pct: cfg.trailing_arm_pct,
+                portfolio_hard_stop_pct: cfg.portfolio_hard_stop_pct,
+            },
+            force_reduce_on_daily_loss: cfg.force_reduce_on_daily_loss,
+            momentum: cfg.strategies.momentum.clone(),
+        }
+    }
+}
+
+/// Top-level config keys whose value differs, split into (hot, restart-only).
+fn changed_keys(old: &Config, new: &Config) -> Result<(Vec<String>, Vec<String>)> {
+    let old = serde_json::to_value(old)?;
+    let new = serde_json::to_value(new)?;
+    let (Some(old), Some(new)) = (old.as_object(), new.as_object()) else {
+        return Ok((vec![], vec![]));
+    };
+
+    let mut hot = vec![];
+    let mut cold = vec![];
+    for (k, v) in new {
+        if old.get(k) == Some(v) {
+            continue;
+        }
+        if is_hot(k) {
+            let before = old.get(k).cloned().unwrap_or_default();
+            hot.push(format!("{k}: {before} -> {v}"));
+        } else {
+            cold.push(k.clone());
+        }
+    }
+    Ok((hot, cold))
+}
+
+/// Current `.env` as a map (systemd's EnvironmentFile is read only at start).
+/// Parsed, never loaded: `set_var` is unsound with the runtime's threads up, and
+/// would put `SIE_KEYSTORE_PASSPHRASE` back into the environment.
+fn read_dotenv() -> HashMap<String, String> {
+    match dotenvy::dotenv_iter() {
+        Ok(iter) => iter
+            .filter_map(|item| match item {
+                Ok(kv) => Some(kv),
+                Err(e) => {
+                    warn!(error = %e, "config.reload.dotenv_invalid_line");
+                    None
+                }
+            })
+            .collect(),
+        Err(_) => HashMap::new(),
+    }
+}
+
+fn config_mtime(dotenv: &HashMap<String, String>) -> Option<SystemTime> {
+    let path = env_nonempty(dotenv, "SIE_CONFIG").unwrap_or_else(|| "./solbot.toml".to_string());
+    std::fs::metadata(Path::new(&path)).and_then(|m| m.modified()).ok()
+}
+
+/// Re-loads config on SIGHUP (`hup`, registered early in boot) or when the
+/// config file changes, validates it and publishes new [`LiveParams`]. Invalid
+/// configs are rejected and the running parameters stay in place.
+pub async fn watch_config(
+    mut current: Config,
+    mut hup: Signal,
+    tx: watch::Sender<LiveParams>,
+    notifier: Notifier,
+) -> Result<()> {
+    let mut poll = tokio::time::interval(Duration::from_secs(5));
+    let mut dotenv = read_dotenv();
+    let mut last_mtime = config_mtime(&dotenv);
+
+    loop {
+        let trigger = tokio::select! {
+            _ = hup.recv() => "sighup",
+            _ = poll.tick() => {
+                let mtime = config_mtime(&dotenv);
+                if mtime == last_mtime {
+                    continue;
+                }
+                last_mtime = mtime;
+                "file_changed"
+            }
+        };
+
+        // Pick up edits to .env as well, without touching the process environment.
+        dotenv = read_dotenv();
+        let (cfg, report) = match Config::load_with_dotenv(&dotenv) {
+            Ok(r) => r,
+            Err(e) => {
+                warn!(trigger, error = %e, "config.reload.rejected");
+                notifier
+                    .alert(Severity::Warning, &format!("[SIE] config reload rejected ({trigger}), keeping current params: {e}"));
+                continue;
+            }
+        };
+        if report.has_errors() {
+            warn!(trigger, %report, "config.reload.rejected");
+            notifier
+                .alert(Severity::Warning, &format!("[SIE] config reload rejected ({trigger}), keeping current params:\n{report}"));
+            continue;
+        }
+
+        let (hot, cold) = changed_keys(&current, &cfg)?;
+        if !cold.is_empty() {
+            warn!(keys = ?cold, "config.reload.restart_required");
+        }
+        if hot.is_empty() {
+            info!(trigger, "config.reload.no_live_changes");
+            if !cold.is_empty() {
+                notifier
+                    .alert(Severity::Warning, &format!(
+                        "[SIE] config changed but needs a restart to apply: {}",
+                        cold.join(", ")
+                    ));
+            }
+            continue;
+        }
+
+        tx.send_replace(LiveParams::from_config(&cfg));
+        info!(trigger, changes = ?hot, "config.reload.applied");
+
+        let mut msg = format!("[SIE] config reloaded ({trigger}):\n{}", hot.join("\n"));
+        if !cold.is_empty() {
+            msg.push_str(&format!("\nrestart required for: {}", cold.join(", ")));
+        }
+        notifier.alert(Severity::Info, &msg);
+
+        // Only hot keys are considered applied; keep comparing cold keys to the boot config.
+        for (_, apply) in HOT_KEYS {
+            apply(&mut current, &cfg);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hot_keys_name_config_fields_and_apply_only_those() {
+        let mut current = Config::load_layers().unwrap();
+        let fields = serde_json::to_value(&current).unwrap();
+        for (k, _) in HOT_KEYS {
+            assert!(fields.get(k).is_some(), "hot key {k} is not a config field");
+        }
+
+        let mut new = current.clone();
+        new.stop_loss_pct += 0.01;
+        new.strategies.momentum.enabled = !new.strategies.momentum.enabled;
+        new.dry_run = !new.dry_run;
+        let (hot, cold) = changed_keys(&current, &new).unwrap();
+        assert_eq!(hot.len(), 2);
+        assert_eq!(cold, vec!["dry_run".to_string()]);
+
+        for (_, apply) in HOT_KEYS {
+            apply(&mut current, &new);
+        }
+        let (hot, cold) = changed_keys(&current, &new).unwrap();
+        assert!(hot.is_empty());
+        assert_eq!(cold, vec!["dry_run".to_string()]);
+    }
+}
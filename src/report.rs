@@ -0,0 +1,242 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use serde::Serialize;
+
+use crate::ledger::{LedgerEvent, TradeExit};
+
+/// Performance of a set of exits. Closes and partials each count as one exit.
+#[derive(Debug, Clone, Serialize)]
+pub struct Stats {
+    pub trades: usize,
+    pub wins: usize,
+    pub win_rate: f64,
+    pub pnl_usdc: f64,
+    /// Mean PnL per exit (USDC).
+    pub expectancy_usdc: f64,
+    /// Mean PnL in R (1R = size x stop loss at entry); exits without a stop are skipped.
+    pub avg_r: Option<f64>,
+    /// Gross profit / gross loss; `None` without losing exits.
+    pub profit_factor: Option<f64>,
+    /// Largest peak-to-trough fall of cumulative PnL (USDC, >= 0).
+    pub max_drawdown_usdc: f64,
+    pub avg_hold_secs: Option<f64>,
+    /// Mean shortfall of fills against the quote (bps); only confirmed fills.
+    pub avg_slippage_bps: Option<f64>,
+    pub fees_lamports: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Group {
+    pub key: String,
+    #[serde(flatten)]
+    pub stats: Stats,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report {
+    pub since: Option<String>,
+    pub strategy: Option<String>,
+    pub overall: Stats,
+    pub by_strategy: Vec<Group>,
+    pub by_mint: Vec<Group>,
+    pub by_exit_reason: Vec<Group>,
+}
+
+/// Parses `--since`: a span back from now (`7d`, `12h`) or a date (`YYYY-MM-DD`,
+/// start of day in `tz`).
+pub fn parse_since(s: &str, tz: &str) -> Result<DateTime<Utc>> {
+    let s = s.trim();
+    let span = |n: &str, unit: i64| -> Option<DateTime<Utc>> {
+        let n: i64 = n.parse().ok()?;
+        Some(Utc::now() - chrono::Duration::seconds(n * unit))
+    };
+    if let Some(t) = s.strip_suffix('d').and_then(|n| span(n, 86_400)) {
+        return Ok(t);
+    }
+    if let Some(t) = s.strip_suffix('h').and_then(|n| span(n, 3_600)) {
+        return Ok(t);
+    }
+    let tz: chrono_tz::Tz = tz.parse().map_err(|_| anyhow!("invalid tz: {tz}"))?;
+    let day = NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| anyhow!("--since: expected 7d, 12h or YYYY-MM-DD, got {s}"))?;
+    let start = day.and_hms_opt(0, 0, 0).expect("midnight is valid");
+    tz.from_local_datetime(&start)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+        .ok_or_else(|| anyhow!("--since: {s} does not exist in {tz}"))
+}
+
+/// Builds the report from ledger events (oldest first).
+pub fn build(events: &[LedgerEvent], since: Option<DateTime<Utc>>, strategy: Option<&str>) -> Report {
+    let exits: Vec<&TradeExit> = events
+        .iter()
+        .filter_map(|e| e.exit())
+        .filter(|x| strategy.is_none_or(|s| x.strategy == s))
+        .filter(|x| match since {
+            Some(t) => DateTime::parse_from_rfc3339(&x.ts).is_ok_and(|ts| ts >= t),
+            None => true,
+        })
+        .collect();
+
+    Report {
+        since: since.map(|t| t.to_rfc3339()),
+        strategy: strategy.map(str::to_string),
+        overall: stats(&exits),
+        by_strategy: group(&exits, |x| x.strategy.clone()),
+        by_mint: group(&exits, |x| x.base_mint.clone()),
+        by_exit_reason: group(&exits, |x| x.reason.to_string()),
+    }
+}
+
+fn group(exits: &[&TradeExit], key: impl Fn(&TradeExit) -> String) -> Vec<Group> {
+    let mut groups: BTreeMap<String, Vec<&TradeExit>> = BTreeMap::new();
+    for x in exits {
+        groups.entry(key(x)).or_default().push(x);
+    }
+    let mut out: Vec<Group> = groups
+        .into_iter()
+        .map(|(key, xs)| Group { key, stats: stats(&xs) })
+        .collect();
+    out.sort_by_key(|g| std::cmp::Reverse(g.stats.trades));
+    out
+}
+
+fn mean(v: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, n) = v.fold((0.0, 0usize), |(s, n), x| (s + x, n + 1));
+    (n > 0).then(|| sum / n as f64)
+}
+
+fn stats(exits: &[&TradeExit]) -> Stats {
+    let trades = exits.len();
+    let wins = exits.iter().filter(|x| x.pnl_usdc > 0.0).count();
+    let pnl_usdc = exits.iter().fold(0.0, |acc, x| acc + x.pnl_usdc);
+    let gross_profit: f64 = exits.iter().map(|x| x.pnl_usdc.max(0.0)).sum();
+    let gross_loss: f64 = exits.iter().map(|x| (-x.pnl_usdc).max(0.0)).sum();
+
+    let (mut cum, mut peak, mut max_drawdown_usdc) = (0.0f64, 0.0f64, 0.0f64);
+    for x in exits {
+        cum += x.pnl_usdc;
+        peak = peak.max(cum);
+        max_drawdown_usdc = max_drawdown_usdc.max(peak - cum);
+    }
+
+    Stats {
+        trades,
+        wins,
+        win_rate: if trades > 0 { wins as f64 / trades as f64 } else { 0.0 },
+        pnl_usdc,
+        expectancy_usdc: if trades > 0 { pnl_usdc / trades as f64 } else { 0.0 },
+        avg_r: mean(exits.iter().filter_map(|x| {
+            let risk = x.size_usdc * x.stop_loss_pct;
+            (risk > 0.0).then(|| x.pnl_usdc / risk)
+        })),
+        profit_factor: (gross_loss > 0.0).then(|| gross_profit / gross_loss),
+        max_drawdown_usdc,
+        avg_hold_secs: mean(exits.iter().filter_map(|x| x.hold_secs).map(|s| s as f64)),
+        avg_slippage_bps: mean(exits.iter().filter_map(|x| x.fill.slippage_bps())),
+        fees_lamports: exits.iter().filter_map(|x| x.fill.fee_lamports).sum(),
+    }
+}
+
+fn opt(v: Option<f64>, prec: usize) -> String {
+    v.map_or("-".into(), |v| format!("{v:.prec$}"))
+}
+
+fn hold(secs: Option<f64>) -> String {
+    match secs {
+        Some(s) if s >= 3_600.0 => format!("{:.1}h", s / 3_600.0),
+        Some(s) => format!("{:.0}m", s / 60.0),
+        None => "-".into(),
+    }
+}
+
+const MD_HEADER: &str = "| | trades | win rate | PnL (USDC) | expectancy | avg R | PF | max DD | hold | slippage (bps) |\n|---|---:|---:|---:|---:|---:|---:|---:|---:|---:|\n";
+
+fn md_row(s: &mut String, key: &str, st: &Stats) {
+    let _ = writeln!(
+        s,
+        "| {key} | {} | {:.0}% | {:.2} | {:.2} | {} | {} | {:.2} | {} | {} |",
+        st.trades,
+        st.win_rate * 100.0,
+        st.pnl_usdc,
+        st.expectancy_usdc,
+        opt(st.avg_r, 2),
+        opt(st.profit_factor, 2),
+        st.max_drawdown_usdc,
+        hold(st.avg_hold_secs),
+        opt(st.avg_slippage_bps, 1),
+    );
+}
+
+pub fn to_markdown(r: &Report) -> String {
+    let mut s = String::from("# Performance report\n\n");
+    let _ = writeln!(
+        s,
+        "Since: {} · Strategy: {} · Fees: {} lamports\n",
+        r.since.as_deref().unwrap_or("all"),
+        r.strategy.as_deref().unwrap_or("all"),
+        r.overall.fees_lamports
+    );
+    s.push_str(MD_HEADER);
+    md_row(&mut s, "**all**", &r.overall);
+
+    for (title, groups) in [
+        ("By strategy", &r.by_strategy),
+        ("By mint", &r.by_mint),
+        ("By exit reason", &r.by_exit_reason),
+    ] {
+        let _ = writeln!(s, "\n## {title}\n");
+        s.push_str(MD_HEADER);
+        for g in groups {
+            md_row(&mut s, &g.key, &g.stats);
+        }
+    }
+    s
+}
+
+fn csv_field(v: &str) -> String {
+    if v.contains([',', '"', '\n']) {
+        format!("\"{}\"", v.replace('"', "\"\""))
+    } else {
+        v.to_string()
+    }
+}
+
+/// One row per group; `dimension` is all | strategy | mint | exit_reason.
+pub fn to_csv(r: &Report) -> String {
+    let mut s = String::from(
+        "dimension,key,trades,wins,win_rate,pnl_usdc,expectancy_usdc,avg_r,profit_factor,max_drawdown_usdc,avg_hold_secs,avg_slippage_bps,fees_lamports\n",
+    );
+    let num = |v: Option<f64>| v.map_or(String::new(), |v| format!("{v:.6}"));
+    let mut row = |dim: &str, key: &str, st: &Stats| {
+        let _ = writeln!(
+            s,
+            "{dim},{},{},{},{:.6},{:.6},{:.6},{},{},{:.6},{},{},{}",
+            csv_field(key),
+            st.trades,
+            st.wins,
+            st.win_rate,
+            st.pnl_usdc,
+            st.expectancy_usdc,
+            num(st.avg_r),
+            num(st.profit_factor),
+            st.max_drawdown_usdc,
+            num(st.avg_hold_secs),
+            num(st.avg_slippage_bps),
+            st.fees_lamports,
+        );
+    };
+    row("all", "", &r.overall);
+    for (dim, groups) in [
+        ("strategy", &r.by_strategy),
+        ("mint", &r.by_mint),
+        ("exit_reason", &r.by_exit_reason),
+    ] {
+        for g in groups {
+            row(dim, &g.key, &g.stats);
+        }
+    }
+    s
+}
@@ -1,3 +1,6 @@
+use std::fmt;
+
+use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,10 +39,55 @@ pub enum ExitReason {
     Other,
 }
 
+impl ExitReason {
+    /// Short label for humans (journal, reports, alerts). Stable: reports and
+    /// dashboards group on it. The ledger keeps the variant name.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::StopLoss => "SL",
+            Self::TrailingStop => "trailing-SL",
+            Self::TakeProfit => "TP",
+            Self::DailyLossLimit => "daily-loss",
+            Self::HardStop => "hard-stop",
+            Self::Manual => "manual",
+            Self::Other => "other",
+        }
+    }
+}
+
+impl fmt::Display for ExitReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.label())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct DailyPnl {
     pub day_key: String, // YYYY-MM-DD in configured TZ
     pub realized_pnl_usdc: f64,
+
+    /// Mark-to-market PnL of open positions (vs entry), refreshed every positions tick.
+    #[serde(default)]
+    pub unrealized_pnl_usdc: f64,
+
+    /// Unrealized PnL carried over from previous days when this day started, so
+    /// only today's move of carried positions counts against today's limit.
+    #[serde(default)]
+    pub unrealized_at_day_start_usdc: f64,
+
+    /// Positions closed today, and how many of them with positive PnL.
+    #[serde(default)]
+    pub trades: u32,
+    #[serde(default)]
+    pub wins: u32,
+}
+
+/// A finished trading day, as returned by [`RiskState::rollover_day_if_needed`].
+#[derive(Debug, Clone)]
+pub struct ClosedDay {
+    pub daily: DailyPnl,
+    pub start_equity_usdc: Option<f64>,
+    pub end_equity_usdc: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,6 +96,15 @@ pub struct RiskState {
     pub daily: DailyPnl,
     pub starting_balance_usdc: f64,
     pub current_balance_usdc: f64,
+
+    /// Marked-to-market equity at the start of the trading day (USDC).
+    /// `None` until the balance oracle has produced a snapshot.
+    #[serde(default)]
+    pub day_start_equity_usdc: Option<f64>,
+
+    /// Last marked-to-market equity (USDC + SOL + open positions).
+    #[serde(default)]
+    pub equity_usdc: Option<f64>,
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -55,6 +112,7 @@ pub enum RiskEvent {
     None,
     EnterReadOnly,      // daily -3% hit
     EnterEmergencyStop, // portfolio -20% hit
+    EnterReadOnlyUnrealized, // daily -3% hit counting open positions mark-to-market
 }
 
 impl RiskState {
@@ -64,18 +122,38 @@ impl RiskState {
             daily: DailyPnl {
                 day_key,
                 realized_pnl_usdc: 0.0,
+                unrealized_pnl_usdc: 0.0,
+                unrealized_at_day_start_usdc: 0.0,
+                trades: 0,
+                wins: 0,
             },
             starting_balance_usdc,
             current_balance_usdc: starting_balance_usdc,
+            day_start_equity_usdc: None,
+            equity_usdc: None,
         }
     }
 
-    pub fn rollover_day_if_needed(&mut self, new_day_key: String) {
-        if self.daily.day_key != new_day_key {
-            self.daily.day_key = new_day_key;
-            self.daily.realized_pnl_usdc = 0.0;
-            // keep mode as-is (ReadOnly remains until operator decides otherwise)
+    /// Starts a new trading day if the key changed; returns the day that ended.
+    pub fn rollover_day_if_needed(&mut self, new_day_key: String) -> Option<ClosedDay> {
+        if self.daily.day_key == new_day_key {
+            return None;
         }
+        let closed = ClosedDay {
+            daily: self.daily.clone(),
+            start_equity_usdc: self.day_start_equity_usdc,
+            end_equity_usdc: self.equity_usdc,
+        };
+
+        self.daily.day_key = new_day_key;
+        self.daily.realized_pnl_usdc = 0.0;
+        self.daily.unrealized_at_day_start_usdc = self.daily.unrealized_pnl_usdc;
+        self.daily.trades = 0;
+        self.daily.wins = 0;
+        // new day starts from the last marked equity (if any)
+        self.day_start_equity_usdc = self.equity_usdc;
+        // keep mode as-is (ReadOnly remains until operator decides otherwise)
+        Some(closed)
     }
 
     /// Registers realized PnL, updates mode if limits are breached, and returns an event to act on.
@@ -83,7 +161,14 @@ impl RiskState {
         let prev_mode = self.mode;
 
         self.daily.realized_pnl_usdc += pnl_usdc;
-        self.current_balance_usdc += pnl_usdc;
+        self.daily.trades += 1;
+        if pnl_usdc > 0.0 {
+            self.daily.wins += 1;
+        }
+        // Marked equity already holds this position's PnL; only the estimate moves.
+        if self.equity_usdc.is_none() {
+            self.current_balance_usdc += pnl_usdc;
+        }
 
         // Rule: PROHIBITED to lose more than X% of total capital per day.
         let max_loss = -params.max_daily_loss_pct * params.capital_usdc;
@@ -92,16 +177,99 @@ impl RiskState {
         }
 
         // Hard stop based on total balance drawdown vs starting balance.
-        let dd = (self.current_balance_usdc - self.starting_balance_usdc) / self.starting_balance_usdc;
-        if dd <= -params.portfolio_hard_stop_pct {
+        if self.hard_stop_breached(params) {
             self.mode = BotMode::EmergencyStop;
         }
 
-        match (prev_mode, self.mode) {
-            (BotMode::Trading, BotMode::ReadOnly) => RiskEvent::EnterReadOnly,
-            (_, BotMode::EmergencyStop) if prev_mode != BotMode::EmergencyStop => RiskEvent::EnterEmergencyStop,
-            _ => RiskEvent::None,
+        mode_event(prev_mode, self.mode)
+    }
+
+    /// Updates open-position mark-to-market and applies the daily loss rule to
+    /// realized + unrealized PnL.
+    pub fn register_unrealized_pnl(&mut self, params: &RiskParams, unrealized_usdc: f64) -> RiskEvent {
+        self.daily.unrealized_pnl_usdc = unrealized_usdc;
+
+        if self.mode == BotMode::Trading && self.daily_loss_breached(params) {
+            self.mode = BotMode::ReadOnly;
+            return RiskEvent::EnterReadOnlyUnrealized;
         }
+        RiskEvent::None
+    }
+
+    /// Today's PnL for the daily loss rule: the marked-to-market equity change
+    /// since the start of the day once the balance oracle runs, else realized
+    /// PnL plus today's change in unrealized PnL.
+    pub fn daily_total_pnl_usdc(&self) -> f64 {
+        match (self.equity_usdc, self.day_start_equity_usdc) {
+            (Some(equity), Some(day_start)) => equity - day_start,
+            _ => self.daily.realized_pnl_usdc + self.daily.unrealized_pnl_usdc - self.daily.unrealized_at_day_start_usdc,
+        }
+    }
+
+    pub fn daily_loss_breached(&self, params: &RiskParams) -> bool {
+        self.daily_total_pnl_usdc() <= -params.daily_loss_limit_usdc()
+    }
+
+    /// Registers a marked-to-market equity snapshot from the balance oracle.
+    ///
+    /// Equity replaces the estimated balance, so the daily loss rule (equity
+    /// vs the start of the day) and the portfolio hard stop include unrealized
+    /// losses, and also moves of the SOL held in the wallet. The first snapshot
+    /// with funds rebases `starting_balance_usdc` to the real wallet value.
+    pub fn register_equity(&mut self, params: &RiskParams, equity_usdc: f64) -> RiskEvent {
+        let prev_mode = self.mode;
+
+        if self.equity_usdc.is_none() || self.starting_balance_usdc <= 0.0 {
+            self.starting_balance_usdc = equity_usdc;
+        }
+        self.day_start_equity_usdc.get_or_insert(equity_usdc);
+        self.equity_usdc = Some(equity_usdc);
+        self.current_balance_usdc = equity_usdc;
+
+        if self.mode == BotMode::Trading && self.daily_loss_breached(params) {
+            self.mode = BotMode::ReadOnly;
+        }
+        if self.hard_stop_breached(params) {
+            self.mode = BotMode::EmergencyStop;
+        }
+
+        mode_event(prev_mode, self.mode)
+    }
+
+    /// Drawdown of the current balance vs the starting balance reached the
+    /// portfolio hard stop. Never true without a positive baseline.
+    fn hard_stop_breached(&self, params: &RiskParams) -> bool {
+        if self.starting_balance_usdc <= 0.0 {
+            return false;
+        }
+        let dd = (self.current_balance_usdc - self.starting_balance_usdc) / self.starting_balance_usdc;
+        dd <= -params.portfolio_hard_stop_pct
+    }
+
+    /// Operator mode override.
+    ///
+    /// Resuming is refused while today's loss limit is still breached. Resuming
+    /// from `EmergencyStop` rebases the hard-stop baseline to the current balance.
+    pub fn operator_set_mode(&mut self, params: &RiskParams, to: BotMode) -> Result<()> {
+        if self.mode == to {
+            return Err(anyhow!("mode is already {to:?}"));
+        }
+
+        if to == BotMode::Trading {
+            if self.daily_loss_breached(params) {
+                return Err(anyhow!(
+                    "daily loss limit still breached (pnl=${:.2}, limit=${:.2}); resume after day rollover",
+                    self.daily_total_pnl_usdc(),
+                    params.daily_loss_limit_usdc()
+                ));
+            }
+            if self.mode == BotMode::EmergencyStop {
+                self.starting_balance_usdc = self.current_balance_usdc;
+            }
+        }
+
+        self.mode = to;
+        Ok(())
     }
 
     pub fn can_open_new_position(&self, params: &RiskParams, open_positions: usize) -> bool {
@@ -109,8 +277,81 @@ impl RiskState {
     }
 }
 
+fn mode_event(prev_mode: BotMode, mode: BotMode) -> RiskEvent {
+    match (prev_mode, mode) {
+        (BotMode::Trading, BotMode::ReadOnly) => RiskEvent::EnterReadOnly,
+        (_, BotMode::EmergencyStop) if prev_mode != BotMode::EmergencyStop => RiskEvent::EnterEmergencyStop,
+        _ => RiskEvent::None,
+    }
+}
+
 impl RiskParams {
     pub fn daily_loss_limit_usdc(&self) -> f64 {
         self.max_daily_loss_pct * self.capital_usdc
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(max_daily_loss_pct: f64) -> RiskParams {
+        RiskParams {
+            capital_usdc: 1000.0,
+            position_size_usdc: 100.0,
+            max_open_positions: 3,
+            max_daily_loss_pct,
+            stop_loss_pct: 0.10,
+            take_profit_pct: 0.40,
+            trailing_arm_pct: 0.15,
+            portfolio_hard_stop_pct: 0.20,
+        }
+    }
+
+    #[test]
+    fn realized_loss_is_not_counted_twice_against_marked_equity() {
+        let params = params(0.5);
+        let mut st = RiskState::new("2026-01-01".into(), 1000.0);
+        st.register_equity(&params, 1000.0);
+        // The open position is already marked 150 down...
+        st.register_equity(&params, 850.0);
+        // ...so booking that loss must not take equity to 700 (-30%).
+        let ev = st.register_realized_pnl(&params, -150.0);
+        assert_eq!(ev, RiskEvent::None);
+        assert_eq!(st.mode, BotMode::Trading);
+        assert_eq!(st.current_balance_usdc, 850.0);
+    }
+
+    #[test]
+    fn realized_pnl_moves_the_estimate_without_the_oracle() {
+        let params = params(0.5);
+        let mut st = RiskState::new("2026-01-01".into(), 1000.0);
+        assert_eq!(st.register_realized_pnl(&params, -199.0), RiskEvent::None);
+        assert_eq!(st.register_realized_pnl(&params, -1.0), RiskEvent::EnterEmergencyStop);
+        assert_eq!(st.current_balance_usdc, 800.0);
+    }
+
+    #[test]
+    fn daily_loss_rule_runs_on_equity_since_the_start_of_the_day() {
+        let params = params(0.03);
+        let mut st = RiskState::new("2026-01-01".into(), 1000.0);
+        st.register_equity(&params, 1000.0);
+        assert_eq!(st.register_equity(&params, 975.0), RiskEvent::None);
+        assert_eq!(st.register_equity(&params, 969.0), RiskEvent::EnterReadOnly);
+        assert_eq!(st.mode, BotMode::ReadOnly);
+
+        // The next day measures from the last equity.
+        st.rollover_day_if_needed("2026-01-02".into());
+        st.mode = BotMode::Trading;
+        assert_eq!(st.register_equity(&params, 950.0), RiskEvent::None);
+        assert_eq!(st.daily_total_pnl_usdc(), 950.0 - 969.0);
+    }
+
+    #[test]
+    fn hard_stop_needs_a_positive_baseline() {
+        let params = params(0.5);
+        let mut st = RiskState::new("2026-01-01".into(), 0.0);
+        assert_eq!(st.register_realized_pnl(&params, -10.0), RiskEvent::None);
+        assert_eq!(st.mode, BotMode::Trading);
+    }
+}
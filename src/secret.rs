@@ -0,0 +1,65 @@
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+
+/// A sensitive config value (API-keyed URLs, webhooks, tokens, key paths).
+///
+/// `Debug` and `Serialize` never show the value, only a short fingerprint so
+/// operators can tell whether two deployments use the same secret.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    /// The raw value. Only pass it to the client that needs it; never log it.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// First 8 hex chars of SHA-256(value), e.g. `sha256:1a2b3c4d`.
+    pub fn fingerprint(&self) -> String {
+        let digest = Sha256::digest(self.0.as_bytes());
+        let hex: String = digest[..4].iter().map(|b| format!("{b:02x}")).collect();
+        format!("sha256:{hex}")
+    }
+
+    /// Host of a URL secret (API keys live in the path/query), if it parses.
+    pub fn url_host(&self) -> Option<String> {
+        reqwest::Url::parse(&self.0).ok()?.host_str().map(str::to_string)
+    }
+
+    /// `host [fingerprint]` for URLs, `[fingerprint]` otherwise.
+    pub fn summary(&self) -> String {
+        match self.url_host() {
+            Some(host) => format!("{host} [{}]", self.fingerprint()),
+            None => format!("[{}]", self.fingerprint()),
+        }
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret(<redacted {}>)", self.fingerprint())
+    }
+}
+
+impl Serialize for Secret {
+    fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&format!("<redacted {}>", self.fingerprint()))
+    }
+}
+
+impl<'de> Deserialize<'de> for Secret {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        String::deserialize(d).map(Secret)
+    }
+}
+
+/// Summary for an optional secret: `-` when unset.
+pub fn summarize(s: Option<&Secret>) -> String {
+    s.map(Secret::summary).unwrap_or_else(|| "-".to_string())
+}
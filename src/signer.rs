@@ -0,0 +1,477 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use base64::Engine as _;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+use tracing::{info, warn};
+
+use crate::config::Config;
+use crate::keystore::UnlockedKeypair;
+use crate::secret::Secret;
+
+/// What a swap is for; remote signers apply their policy to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignContext {
+    pub input_mint: String,
+    pub output_mint: String,
+    /// Estimated USDC value of the swap, as the bot sees it. For logs only:
+    /// [`SignerPolicy`] derives the notional from the transaction itself.
+    pub notional_usdc: f64,
+}
+
+/// Signs swap transactions for the trading wallet.
+#[async_trait]
+pub trait TxSigner: Send + Sync {
+    /// `keypair`, `keystore` or `remote` (for logs).
+    fn kind(&self) -> &'static str;
+    fn pubkey(&self) -> Pubkey;
+    /// Returns `tx` with the wallet signature added; the message must not change.
+    async fn sign(&self, tx: VersionedTransaction, ctx: &SignContext) -> Result<VersionedTransaction>;
+}
+
+// --- in-process signer (plaintext keypair or unlocked keystore) ---
+
+pub struct LocalSigner {
+    key: UnlockedKeypair,
+    kind: &'static str,
+}
+
+impl LocalSigner {
+    /// Unlocks the configured keystore or plaintext keypair (once).
+    pub fn from_config(cfg: &Config) -> Result<Option<Self>> {
+        let kind = if cfg.keystore_path.is_some() { "keystore" } else { "keypair" };
+        Ok(crate::keystore::unlock_from_config(cfg)?.map(|key| Self { key, kind }))
+    }
+}
+
+#[async_trait]
+impl TxSigner for LocalSigner {
+    fn kind(&self) -> &'static str {
+        self.kind
+    }
+
+    fn pubkey(&self) -> Pubkey {
+        self.key.pubkey()
+    }
+
+    async fn sign(&self, tx: VersionedTransaction, _ctx: &SignContext) -> Result<VersionedTransaction> {
+        let kp = self.key.keypair()?;
+        Ok(VersionedTransaction::try_new(tx.message, &[&kp])?)
+    }
+}
+
+// --- remote signer (HTTP) ---
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SignRequest {
+    /// base64(bincode(VersionedTransaction)), unsigned.
+    pub tx: String,
+    #[serde(flatten)]
+    pub ctx: SignContext,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SignResponse {
+    pub tx: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PubkeyResponse {
+    pub pubkey: String,
+}
+
+fn encode_tx(tx: &VersionedTransaction) -> Result<String> {
+    Ok(base64::engine::general_purpose::STANDARD.encode(bincode::serialize(tx)?))
+}
+
+fn decode_tx(raw: &str) -> Result<VersionedTransaction> {
+    let bytes = base64::engine::general_purpose::STANDARD.decode(raw)?;
+    Ok(bincode::deserialize(&bytes)?)
+}
+
+pub struct RemoteSigner {
+    base_url: String,
+    token: Secret,
+    pubkey: Pubkey,
+    http: Client,
+}
+
+impl RemoteSigner {
+    /// Asks the signer for its wallet pubkey; all later signatures are checked against it.
+    pub async fn connect(base_url: &str, token: Secret) -> Result<Self> {
+        let base_url = base_url.trim_end_matches('/').to_string();
+        let http = Client::new();
+        let resp = http
+            .get(format!("{base_url}/v1/pubkey"))
+            .bearer_auth(token.expose())
+            .send()
+            .await
+            .map_err(|e| anyhow!("remote signer unreachable: {}", e.without_url()))?;
+        let PubkeyResponse { pubkey } = read(resp).await?;
+
+        Ok(Self {
+            base_url,
+            token,
+            pubkey: pubkey.parse()?,
+            http,
+        })
+    }
+}
+
+async fn read<T: for<'de> Deserialize<'de>>(resp: reqwest::Response) -> Result<T> {
+    let status = resp.status();
+    if !status.is_success() {
+        let body: serde_json::Value = resp.json().await.unwrap_or_default();
+        return Err(anyhow!(
+            "remote signer {status}: {}",
+            body["error"].as_str().unwrap_or("unknown error")
+        ));
+    }
+    Ok(resp.json().await?)
+}
+
+#[async_trait]
+impl TxSigner for RemoteSigner {
+    fn kind(&self) -> &'static str {
+        "remote"
+    }
+
+    fn pubkey(&self) -> Pubkey {
+        self.pubkey
+    }
+
+    async fn sign(&self, tx: VersionedTransaction, ctx: &SignContext) -> Result<VersionedTransaction> {
+        let resp = self
+            .http
+            .post(format!("{}/v1/sign", self.base_url))
+            .bearer_auth(self.token.expose())
+            .json(&SignRequest {
+                tx: encode_tx(&tx)?,
+                ctx: ctx.clone(),
+            })
+            .send()
+            .await
+            .map_err(|e| anyhow!("remote signer unreachable: {}", e.without_url()))?;
+        let SignResponse { tx: raw } = read(resp).await?;
+        let signed = decode_tx(&raw)?;
+
+        // Don't trust the signer to have signed what we sent.
+        if signed.message != tx.message {
+            return Err(anyhow!("remote signer returned a different message"));
+        }
+        let msg = signed.message.serialize();
+        let ours = signed
+            .message
+            .static_account_keys()
+            .iter()
+            .position(|k| *k == self.pubkey)
+            .and_then(|i| signed.signatures.get(i))
+            .ok_or_else(|| anyhow!("remote signer did not sign for {}", self.pubkey))?;
+        if !ours.verify(self.pubkey.as_ref(), &msg) {
+            return Err(anyhow!("remote signer returned an invalid signature"));
+        }
+        Ok(signed)
+    }
+}
+
+/// Builds the configured signer: `remote` (HTTP), else the keystore, else a
+/// plaintext keypair. `None` when nothing is configured (dry-run).
+pub async fn from_config(cfg: &Config) -> Result<Option<Arc<dyn TxSigner>>> {
+    let signer: Arc<dyn TxSigner> = match cfg.signer.as_str() {
+        "remote" => {
+            let url = cfg
+                .signer_url
+                .as_deref()
+                .ok_or_else(|| anyhow!("SIE_SIGNER=remote requires SIE_SIGNER_URL"))?;
+            let token = cfg
+                .signer_token
+                .clone()
+                .ok_or_else(|| anyhow!("SIE_SIGNER=remote requires SIE_SIGNER_TOKEN"))?;
+            Arc::new(RemoteSigner::connect(url, token).await?)
+        }
+        _ => match LocalSigner::from_config(cfg)? {
+            Some(s) => Arc::new(s),
+            None => return Ok(None),
+        },
+    };
+    info!(kind = signer.kind(), pubkey = %signer.pubkey(), "signer.ready");
+    Ok(Some(signer))
+}
+
+// --- stand-in signer server (`solbot signer serve`) ---
+
+/// USDC base units per USDC.
+const USDC_UNITS: f64 = 1e6;
+
+/// What the signer agrees to sign, independent of the bot.
+#[derive(Debug, Clone)]
+pub struct SignerPolicy {
+    pub allowed_programs: HashSet<Pubkey>,
+    /// Cap on the USDC spent by one transaction. Sells into USDC only reduce
+    /// risk and are never capped (stop losses must always go through).
+    pub max_notional_usdc: f64,
+}
+
+impl SignerPolicy {
+    pub fn new(extra_programs: &[String], max_notional_usdc: f64) -> Result<Self> {
+        let mut allowed_programs = HashSet::new();
+        for p in crate::firewall::ALLOWED_PROGRAMS.iter().copied().chain(extra_programs.iter().map(String::as_str)) {
+            allowed_programs.insert(p.parse().map_err(|_| anyhow!("invalid program id {p}"))?);
+        }
+        Ok(Self {
+            allowed_programs,
+            max_notional_usdc,
+        })
+    }
+
+    pub fn check(&self, tx: &VersionedTransaction, wallet: &Pubkey) -> Result<()> {
+        let keys = tx.message.static_account_keys();
+        if keys.first() != Some(wallet) {
+            return Err(anyhow!("fee payer is not the signer wallet {wallet}"));
+        }
+        // Program ids are always static keys (never loaded from lookup tables).
+        for (i, ix) in tx.message.instructions().iter().enumerate() {
+            let program = keys
+                .get(ix.program_id_index as usize)
+                .ok_or_else(|| anyhow!("instruction {i}: program id index out of range"))?;
+            if !self.allowed_programs.contains(program) {
+                return Err(anyhow!("instruction {i}: program {program} not allowed"));
+            }
+        }
+
+        // Notional from the swap args, not from what the client reports. The
+        // signer can't resolve lookup tables, so the user token accounts must
+        // be static keys (Jupiter never puts them in its shared tables).
+        let swaps = crate::firewall::jupiter_swaps(&tx.message, keys).map_err(|v| anyhow!("{v}"))?;
+        if swaps.is_empty() {
+            return Err(anyhow!("no jupiter swap instruction"));
+        }
+        let usdc = crate::firewall::token_accounts(wallet, &crate::balance::USDC_MINT.parse()?);
+        let mut notional_usdc = 0.0;
+        for s in &swaps {
+            if usdc.contains(&s.destination) {
+                continue;
+            }
+            if !usdc.contains(&s.source) {
+                return Err(anyhow!("swap neither spends nor receives USDC"));
+            }
+            notional_usdc += s.in_amount as f64 / USDC_UNITS;
+        }
+        if notional_usdc > self.max_notional_usdc {
+            return Err(anyhow!(
+                "notional ${notional_usdc:.2} exceeds max ${:.2}",
+                self.max_notional_usdc
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct SignerServer {
+    signer: Arc<LocalSigner>,
+    policy: Arc<SignerPolicy>,
+    token: Secret,
+}
+
+struct ApiError(StatusCode, String);
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(serde_json::json!({ "error": self.1 }))).into_response()
+    }
+}
+
+impl SignerServer {
+    fn authorize(&self, headers: &HeaderMap) -> Result<(), ApiError> {
+        let token = headers
+            .get(axum::http::header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .unwrap_or_default();
+        if crate::control::ct_eq(token.as_bytes(), self.token.expose().as_bytes()) {
+            Ok(())
+        } else {
+            warn!("signer.auth.rejected");
+            Err(ApiError(StatusCode::UNAUTHORIZED, "invalid token".into()))
+        }
+    }
+}
+
+async fn get_pubkey(State(srv): State<SignerServer>, headers: HeaderMap) -> Result<Json<PubkeyResponse>, ApiError> {
+    srv.authorize(&headers)?;
+    Ok(Json(PubkeyResponse {
+        pubkey: srv.signer.pubkey().to_string(),
+    }))
+}
+
+async fn sign(
+    State(srv): State<SignerServer>,
+    headers: HeaderMap,
+    Json(req): Json<SignRequest>,
+) -> Result<Json<SignResponse>, ApiError> {
+    srv.authorize(&headers)?;
+    let bad = |e: anyhow::Error| ApiError(StatusCode::BAD_REQUEST, e.to_string());
+
+    let tx = decode_tx(&req.tx).map_err(bad)?;
+    if let Err(e) = srv.policy.check(&tx, &srv.signer.pubkey()) {
+        warn!(reason = %e, ctx = ?req.ctx, "signer.policy.rejected");
+        return Err(ApiError(StatusCode::FORBIDDEN, format!("policy: {e}")));
+    }
+
+    let signed = srv.signer.sign(tx, &req.ctx).await.map_err(bad)?;
+    info!(ctx = ?req.ctx, "signer.signed");
+    Ok(Json(SignResponse {
+        tx: encode_tx(&signed).map_err(bad)?,
+    }))
+}
+
+fn router(signer: LocalSigner, policy: SignerPolicy, token: Secret) -> Router {
+    let srv = SignerServer {
+        signer: Arc::new(signer),
+        policy: Arc::new(policy),
+        token,
+    };
+    Router::new()
+        .route("/v1/pubkey", get(get_pubkey))
+        .route("/v1/sign", post(sign))
+        .with_state(srv)
+}
+
+/// Serves the stand-in remote signer until the listener fails.
+pub async fn serve(addr: &str, signer: LocalSigner, policy: SignerPolicy, token: Secret) -> Result<()> {
+    let app = router(signer, policy, token);
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    info!(%addr, "signer.listening");
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::{Keypair, Signer};
+    use zeroize::Zeroizing;
+
+    use crate::testutil::{buy, sell, unsigned_tx};
+
+    const TOKEN: &str = "test-token-0123456789";
+
+    fn local(kp: &Keypair) -> LocalSigner {
+        LocalSigner {
+            key: UnlockedKeypair::from_bytes(Zeroizing::new(kp.to_bytes().to_vec())).unwrap(),
+            kind: "keypair",
+        }
+    }
+
+    fn ctx() -> SignContext {
+        SignContext {
+            input_mint: "in".into(),
+            output_mint: "out".into(),
+            notional_usdc: 0.0,
+        }
+    }
+
+    async fn spawn(app: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{addr}")
+    }
+
+    /// A signer that advertises `pubkey` and answers `/v1/sign` with `respond(tx)`.
+    async fn fake_signer(
+        pubkey: Pubkey,
+        respond: impl Fn(VersionedTransaction) -> VersionedTransaction + Send + Sync + 'static,
+    ) -> String {
+        let respond = Arc::new(respond);
+        let app = Router::new()
+            .route(
+                "/v1/pubkey",
+                get(move || async move { Json(PubkeyResponse { pubkey: pubkey.to_string() }) }),
+            )
+            .route(
+                "/v1/sign",
+                post(move |Json(req): Json<SignRequest>| async move {
+                    let tx = respond(decode_tx(&req.tx).unwrap());
+                    Json(SignResponse { tx: encode_tx(&tx).unwrap() })
+                }),
+            );
+        spawn(app).await
+    }
+
+    fn sign_with(kp: &Keypair, mut tx: VersionedTransaction) -> VersionedTransaction {
+        tx.signatures[0] = kp.sign_message(&tx.message.serialize());
+        tx
+    }
+
+    #[tokio::test]
+    async fn remote_signer_accepts_valid_signature() {
+        let kp = Keypair::new();
+        let policy = SignerPolicy::new(&[], 50.0).unwrap();
+        let url = spawn(router(local(&kp), policy, Secret::new(TOKEN))).await;
+
+        let remote = RemoteSigner::connect(&url, Secret::new(TOKEN)).await.unwrap();
+        assert_eq!(remote.pubkey(), kp.pubkey());
+
+        let tx = unsigned_tx(&kp.pubkey(), &[buy(&kp.pubkey(), 10_000_000)], &[]);
+        let signed = remote.sign(tx.clone(), &ctx()).await.unwrap();
+        assert_eq!(signed.message, tx.message);
+        assert!(signed.signatures[0].verify(kp.pubkey().as_ref(), &tx.message.serialize()));
+    }
+
+    #[tokio::test]
+    async fn remote_signer_rejects_tampered_message() {
+        let kp = Arc::new(Keypair::new());
+        let signer = kp.clone();
+        let url = fake_signer(kp.pubkey(), move |mut tx| {
+            // Validly signed, but not what was sent.
+            tx.message.set_recent_blockhash(solana_sdk::hash::Hash::new_unique());
+            sign_with(&signer, tx)
+        })
+        .await;
+
+        let remote = RemoteSigner::connect(&url, Secret::new(TOKEN)).await.unwrap();
+        let tx = unsigned_tx(&kp.pubkey(), &[buy(&kp.pubkey(), 10_000_000)], &[]);
+        let err = remote.sign(tx, &ctx()).await.unwrap_err();
+        assert!(err.to_string().contains("different message"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn remote_signer_rejects_wrong_pubkey() {
+        let wallet = Keypair::new();
+        let other = Keypair::new();
+        // Advertises the wallet, signs with another key.
+        let url = fake_signer(wallet.pubkey(), move |tx| sign_with(&other, tx)).await;
+
+        let remote = RemoteSigner::connect(&url, Secret::new(TOKEN)).await.unwrap();
+        let tx = unsigned_tx(&wallet.pubkey(), &[buy(&wallet.pubkey(), 10_000_000)], &[]);
+        let err = remote.sign(tx, &ctx()).await.unwrap_err();
+        assert!(err.to_string().contains("invalid signature"), "{err}");
+    }
+
+    #[test]
+    fn policy_caps_buys_from_the_transaction_but_not_sells() {
+        let wallet = Keypair::new().pubkey();
+        let policy = SignerPolicy::new(&[], 50.0).unwrap();
+
+        policy.check(&unsigned_tx(&wallet, &[buy(&wallet, 50_000_000)], &[]), &wallet).unwrap();
+        let err = policy
+            .check(&unsigned_tx(&wallet, &[buy(&wallet, 50_000_001)], &[]), &wallet)
+            .unwrap_err();
+        assert!(err.to_string().contains("exceeds max"), "{err}");
+
+        // A sell into USDC worth far more than the cap still goes through.
+        policy.check(&unsigned_tx(&wallet, &[sell(&wallet, u64::MAX)], &[]), &wallet).unwrap();
+    }
+}
@@ -5,7 +5,17 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use crate::risk::{BotMode, RiskState};
+use crate::engine::Fill;
+use crate::risk::{BotMode, ExitReason, RiskState};
+use crate::summary::SummaryState;
+
+/// Serializes load-modify-save cycles on `state.json` across the daemon's loops
+/// and the control API. Hold the guard for the whole tick.
+static STATE_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+pub async fn lock() -> tokio::sync::MutexGuard<'static, ()> {
+    STATE_LOCK.lock().await
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Position {
@@ -32,6 +42,34 @@ pub struct Position {
     // tx ids
     pub buy_tx: Option<String>,
     pub sell_tx: Option<String>,
+
+    /// RFC 3339 UTC, set when the buy fills.
+    #[serde(default)]
+    pub opened_at: Option<String>,
+
+    /// Last price the positions loop marked it at; stands in while quotes fail.
+    #[serde(default)]
+    pub mark_price: Option<f64>,
+
+    /// Sell sent but not yet seen confirmed. The position stays open (and
+    /// counted) until the sell lands, fails or expires.
+    #[serde(default)]
+    pub pending_sell: Option<PendingSell>,
+}
+
+/// What a close needs to be booked once its sell lands.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingSell {
+    pub signature: String,
+    pub reason: ExitReason,
+    pub note: String,
+    /// Mark that triggered the exit.
+    pub price: f64,
+    pub fill: Fill,
+    /// Past this block height the sell can no longer land.
+    pub last_valid_block_height: Option<u64>,
+    /// RFC 3339 UTC.
+    pub sent_at: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,6 +82,24 @@ pub struct PersistedState {
 
     // Open positions
     pub positions: Vec<Position>,
+
+    /// Operator mode transitions (audit trail).
+    #[serde(default)]
+    pub mode_history: Vec<ModeChange>,
+
+    /// Alerts and mode changes awaiting the next daily/weekly summary.
+    #[serde(default)]
+    pub summary: SummaryState,
+}
+
+/// One operator-initiated mode transition.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModeChange {
+    pub ts: String, // RFC3339 UTC
+    pub from: BotMode,
+    pub to: BotMode,
+    pub operator: String,
+    pub reason: String,
 }
 
 pub struct StateStore {
@@ -80,6 +136,8 @@ impl PersistedState {
             mode: risk.mode,
             risk,
             positions: vec![],
+            mode_history: vec![],
+            summary: SummaryState::default(),
         }
     }
 
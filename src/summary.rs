@@ -0,0 +1,128 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::config::Config;
+use crate::journal::Format;
+use crate::ledger::TradeExit;
+use crate::notifier::events::{DailySummary, SummaryPeriod};
+use crate::notifier::Tally;
+use crate::risk::{BotMode, ClosedDay};
+
+/// Summary inputs only the notifier sees (alerts, mode changes), kept in
+/// state so a restart doesn't lose them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SummaryState {
+    /// Since the current trading day started.
+    pub day: Tally,
+    /// Week being accumulated for the weekly roll-up.
+    pub week: Option<WeekTally>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeekTally {
+    /// ISO week, `2026-W42`.
+    pub week_key: String,
+    pub start_equity_usdc: Option<f64>,
+    pub tally: Tally,
+}
+
+/// What the rollover needs from the config; cloned into each loop.
+#[derive(Debug, Clone)]
+pub struct SummaryOptions {
+    pub tz: String,
+    pub trade_ledger_path: String,
+    pub trading_journal_path: String,
+    pub journal: Format,
+    pub weekly: bool,
+}
+
+impl SummaryOptions {
+    pub fn from_config(cfg: &Config) -> Result<Self> {
+        Ok(Self {
+            tz: cfg.tz.clone(),
+            trade_ledger_path: cfg.trade_ledger_path.clone(),
+            trading_journal_path: cfg.trading_journal_path.clone(),
+            journal: Format::from_config(cfg)?,
+            weekly: cfg.weekly_summary,
+        })
+    }
+}
+
+/// Summaries for the day that just closed: the daily one, then the weekly
+/// roll-up if `new_day_key` starts a new ISO week and roll-ups are on.
+/// Drains `state.day` (and `state.week` when the week is summarized).
+pub fn close_day(
+    state: &mut SummaryState,
+    opts: &SummaryOptions,
+    closed: ClosedDay,
+    new_day_key: &str,
+    open_positions: usize,
+    mode: BotMode,
+) -> Vec<DailySummary> {
+    let events = crate::ledger::read(&opts.trade_ledger_path).unwrap_or_else(|e| {
+        warn!(error = %e, "summary.ledger_read_failed");
+        vec![]
+    });
+    let exits: Vec<(String, &TradeExit)> = events
+        .iter()
+        .filter_map(|e| e.exit())
+        .filter_map(|x| Some((crate::time::day_key_of(&x.ts, &opts.tz)?, x)))
+        .collect();
+
+    let day = closed.daily.day_key.clone();
+    let day_exits: Vec<&TradeExit> = exits.iter().filter(|(d, _)| *d == day).map(|(_, x)| *x).collect();
+    let tally = std::mem::take(&mut state.day);
+    let end_equity_usdc = closed.end_equity_usdc;
+    let unrealized_pnl_usdc = closed.daily.unrealized_pnl_usdc;
+
+    let mut week_done = None;
+    if opts.weekly {
+        if let Some(week_key) = crate::time::week_key(&day) {
+            if state.week.as_ref().is_none_or(|w| w.week_key != week_key) {
+                state.week = Some(WeekTally {
+                    week_key: week_key.clone(),
+                    start_equity_usdc: closed.start_equity_usdc,
+                    tally: Tally::default(),
+                });
+            }
+            if let Some(w) = state.week.as_mut() {
+                w.tally.absorb(tally.clone());
+            }
+            if crate::time::week_key(new_day_key).as_deref() != Some(week_key.as_str()) {
+                week_done = state.week.take();
+            }
+        }
+    }
+
+    let mut out = vec![DailySummary::new(closed, open_positions, mode)
+        .with_exits(&day_exits)
+        .with_tally(tally)];
+
+    if let Some(w) = week_done {
+        let week_exits: Vec<&TradeExit> = exits
+            .iter()
+            .filter(|(d, _)| crate::time::week_key(d).as_deref() == Some(w.week_key.as_str()))
+            .map(|(_, x)| *x)
+            .collect();
+        let summary = DailySummary {
+            period: SummaryPeriod::Week,
+            day_key: w.week_key,
+            realized_pnl_usdc: week_exits.iter().fold(0.0, |acc, x| acc + x.pnl_usdc),
+            unrealized_pnl_usdc,
+            trades: week_exits.len() as u32,
+            wins: week_exits.iter().filter(|x| x.pnl_usdc > 0.0).count() as u32,
+            start_equity_usdc: w.start_equity_usdc,
+            end_equity_usdc,
+            open_positions,
+            mode,
+            fees_lamports: 0,
+            best: None,
+            worst: None,
+            mode_changes: vec![],
+            alerts: 0,
+        };
+        out.push(summary.with_exits(&week_exits).with_tally(w.tally));
+    }
+    out
+}
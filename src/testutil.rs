@@ -0,0 +1,71 @@
+//! Transaction builders shared by the signer and firewall tests.
+
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::{v0, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::firewall::{anchor_discriminator, token_accounts, JUPITER_V6, TOKEN_PROGRAM};
+
+pub const USDC: &str = crate::balance::USDC_MINT;
+pub const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+pub fn key(s: &str) -> Pubkey {
+    s.parse().unwrap()
+}
+
+/// The wallet's SPL Token account for `mint`.
+pub fn ata(wallet: &Pubkey, mint: &str) -> Pubkey {
+    token_accounts(wallet, &key(mint))[0]
+}
+
+/// Jupiter v6 `route` (exact in) from `source` to `destination`, spending
+/// `in_amount` base units, with an empty route plan.
+pub fn jupiter_route(wallet: &Pubkey, source: Pubkey, destination: Pubkey, in_amount: u64) -> Instruction {
+    let jupiter = key(JUPITER_V6);
+    let mut data = anchor_discriminator("route").to_vec();
+    data.extend(0u32.to_le_bytes()); // route_plan: empty vec
+    data.extend(in_amount.to_le_bytes());
+    data.extend(1u64.to_le_bytes()); // quoted_out_amount
+    data.extend(50u16.to_le_bytes()); // slippage_bps
+    data.push(0); // platform_fee_bps
+    Instruction {
+        program_id: jupiter,
+        accounts: vec![
+            AccountMeta::new_readonly(key(TOKEN_PROGRAM), false),
+            AccountMeta::new_readonly(*wallet, true),
+            AccountMeta::new(source, false),
+            AccountMeta::new(destination, false),
+            AccountMeta::new_readonly(jupiter, false), // destination_token_account: none
+            AccountMeta::new_readonly(Pubkey::new_unique(), false), // destination_mint
+            AccountMeta::new_readonly(jupiter, false), // platform_fee_account: none
+            AccountMeta::new_readonly(Pubkey::new_unique(), false), // event_authority
+            AccountMeta::new_readonly(jupiter, false),
+        ],
+        data,
+    }
+}
+
+/// Buys BONK with `usdc_units` USDC base units.
+pub fn buy(wallet: &Pubkey, usdc_units: u64) -> Instruction {
+    jupiter_route(wallet, ata(wallet, USDC), ata(wallet, BONK), usdc_units)
+}
+
+/// Sells `bonk_units` BONK base units into USDC.
+pub fn sell(wallet: &Pubkey, bonk_units: u64) -> Instruction {
+    jupiter_route(wallet, ata(wallet, BONK), ata(wallet, USDC), bonk_units)
+}
+
+/// Unsigned v0 transaction paid by `wallet`; accounts found in `tables` are
+/// loaded through them.
+pub fn unsigned_tx(wallet: &Pubkey, ixs: &[Instruction], tables: &[AddressLookupTableAccount]) -> VersionedTransaction {
+    let message = v0::Message::try_compile(wallet, ixs, tables, Hash::new_unique()).unwrap();
+    let signatures = vec![Signature::default(); message.header.num_required_signatures as usize];
+    VersionedTransaction {
+        signatures,
+        message: VersionedMessage::V0(message),
+    }
+}
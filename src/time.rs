@@ -9,3 +9,16 @@ pub fn day_key(tz: &str) -> Result<String> {
     let now = chrono::Utc::now().with_timezone(&tz);
     Ok(format!("{:04}-{:02}-{:02}", now.year(), now.month(), now.day()))
 }
+
+/// Trading day (YYYY-MM-DD in `tz`) of an RFC 3339 timestamp.
+pub fn day_key_of(ts: &str, tz: &str) -> Option<String> {
+    let tz: chrono_tz::Tz = tz.parse().ok()?;
+    let t = chrono::DateTime::parse_from_rfc3339(ts).ok()?.with_timezone(&tz);
+    Some(format!("{:04}-{:02}-{:02}", t.year(), t.month(), t.day()))
+}
+
+/// ISO week (`2026-W42`) of a day key.
+pub fn week_key(day_key: &str) -> Option<String> {
+    let week = chrono::NaiveDate::parse_from_str(day_key, "%Y-%m-%d").ok()?.iso_week();
+    Some(format!("{:04}-W{:02}", week.year(), week.week()))
+}
@@ -0,0 +1,463 @@
+use serde::Serialize;
+use std::fmt;
+use std::path::Path;
+
+use crate::config::Config;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Issue {
+    pub severity: Severity,
+    /// File key / env var the issue refers to.
+    pub key: String,
+    pub message: String,
+}
+
+/// Every problem found in a config, not just the first one.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ValidationReport {
+    pub issues: Vec<Issue>,
+}
+
+impl ValidationReport {
+    fn error(&mut self, key: &str, message: impl Into<String>) {
+        self.issues.push(Issue {
+            severity: Severity::Error,
+            key: key.to_string(),
+            message: message.into(),
+        });
+    }
+
+    fn warn(&mut self, key: &str, message: impl Into<String>) {
+        self.issues.push(Issue {
+            severity: Severity::Warning,
+            key: key.to_string(),
+            message: message.into(),
+        });
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.issues.iter().any(|i| i.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Issue> {
+        self.issues.iter().filter(|i| i.severity == Severity::Warning)
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let errors = self.issues.iter().filter(|i| i.severity == Severity::Error).count();
+        write!(
+            f,
+            "config validation: {errors} error(s), {} warning(s)",
+            self.issues.len() - errors
+        )?;
+        for i in &self.issues {
+            let tag = match i.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warn ",
+            };
+            write!(f, "\n  [{tag}] {}: {}", i.key, i.message)?;
+        }
+        Ok(())
+    }
+}
+
+fn check_pct(r: &mut ValidationReport, key: &str, v: f64) {
+    if !(v > 0.0 && v < 1.0) {
+        r.error(key, format!("must be a fraction in (0, 1), got {v} (e.g. 0.03 = 3%)"));
+    }
+}
+
+fn check_url(r: &mut ValidationReport, key: &str, v: &str, schemes: &[&str]) {
+    match reqwest::Url::parse(v) {
+        Ok(u) if schemes.contains(&u.scheme()) => {}
+        Ok(u) => r.error(
+            key,
+            format!("scheme '{}' not allowed (expected {})", u.scheme(), schemes.join("/")),
+        ),
+        Err(e) => r.error(key, format!("not a valid URL: {e}")),
+    }
+}
+
+/// Key file problems are errors for live trading and warnings in dry-run.
+/// Messages leave the path out; it is treated as a secret.
+fn check_keypair_file(r: &mut ValidationReport, key: &str, path: &str, live: bool) {
+    let mut report = |msg: String| if live { r.error(key, msg) } else { r.warn(key, msg) };
+
+    let p = Path::new(path);
+    let meta = match std::fs::metadata(p) {
+        Ok(m) => m,
+        Err(e) => return report(e.to_string()),
+    };
+    if !meta.is_file() {
+        return report("not a file".to_string());
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = meta.permissions().mode() & 0o777;
+        if mode & 0o077 != 0 {
+            report(format!(
+                "file has mode {mode:o}; must not be accessible by group/others (chmod 600)"
+            ));
+        }
+    }
+}
+
+impl Config {
+    /// Cross-field validation. Collects all problems into one report.
+    pub fn validate(&self) -> ValidationReport {
+        let mut r = ValidationReport::default();
+
+        // Risk
+        if self.capital_usdc <= 0.0 {
+            r.error("risk.capital_usdc / SIE_CAPITAL_USDC", "must be > 0");
+        }
+        if self.position_size_usdc <= 0.0 {
+            r.error("risk.position_size_usdc / SIE_POSITION_SIZE_USDC", "must be > 0");
+        }
+        if self.max_open_positions == 0 {
+            r.error("risk.max_open_positions / MAX_OPEN_POSITIONS", "must be >= 1");
+        }
+        let exposure = self.position_size_usdc * self.max_open_positions as f64;
+        if exposure > self.capital_usdc {
+            r.error(
+                "risk.position_size_usdc / SIE_POSITION_SIZE_USDC",
+                format!(
+                    "position_size_usdc * max_open_positions = {exposure:.2} exceeds capital_usdc = {:.2}",
+                    self.capital_usdc
+                ),
+            );
+        }
+
+        check_pct(&mut r, "risk.max_daily_loss_pct / SIE_MAX_DAILY_LOSS_PCT", self.max_daily_loss_pct);
+        check_pct(&mut r, "risk.stop_loss_pct / SIE_STOP_LOSS_PCT", self.stop_loss_pct);
+        check_pct(&mut r, "risk.take_profit_pct / SIE_TAKE_PROFIT_PCT", self.take_profit_pct);
+        check_pct(&mut r, "risk.trailing_arm_pct / SIE_TRAILING_ARM_PCT", self.trailing_arm_pct);
+        check_pct(
+            &mut r,
+            "risk.portfolio_hard_stop_pct / SIE_PORTFOLIO_HARD_STOP_PCT",
+            self.portfolio_hard_stop_pct,
+        );
+        if self.trailing_arm_pct <= self.stop_loss_pct {
+            r.error(
+                "risk.trailing_arm_pct / SIE_TRAILING_ARM_PCT",
+                format!(
+                    "must be greater than stop_loss_pct ({}), otherwise the trailing stop can arm below entry",
+                    self.stop_loss_pct
+                ),
+            );
+        }
+        if self.max_daily_loss_pct >= self.portfolio_hard_stop_pct {
+            r.warn(
+                "risk.max_daily_loss_pct / SIE_MAX_DAILY_LOSS_PCT",
+                "daily loss limit is not below the portfolio hard stop",
+            );
+        }
+        if self.equity_interval_secs < 10 {
+            r.warn(
+                "risk.equity_interval_secs / SIE_EQUITY_INTERVAL_SECS",
+                "values below 10s are clamped to 10s",
+            );
+        }
+
+        // Runtime
+        if self.tz.parse::<chrono_tz::Tz>().is_err() {
+            r.error("runtime.tz / SIE_TZ", format!("'{}' is not an IANA timezone", self.tz));
+        }
+
+        // Journal
+        if let Err(e) = crate::journal::Template::load(&self.journal_lang, self.journal_template_path.as_deref()) {
+            r.error("journal.lang / SIE_JOURNAL_LANG, journal.template / SIE_JOURNAL_TEMPLATE", e.to_string());
+        }
+
+        // Execution
+        if self.slippage_bps == 0 {
+            r.error("execution.slippage_bps / SIE_SLIPPAGE_BPS", "must be > 0");
+        }
+        if self.slippage_bps > self.max_slippage_bps {
+            r.error(
+                "execution.slippage_bps / SIE_SLIPPAGE_BPS",
+                format!("cannot exceed max_slippage_bps ({})", self.max_slippage_bps),
+            );
+        }
+        if self.max_slippage_bps > 10_000 {
+            r.error("execution.max_slippage_bps / SIE_MAX_SLIPPAGE_BPS", "must be <= 10000 (100%)");
+        }
+
+        // URLs
+        check_url(&mut r, "rpc.helius_http_url / HELIUS_HTTP_URL", self.helius_http_url.expose(), &["http", "https"]);
+        check_url(&mut r, "rpc.helius_wss_url / HELIUS_WSS_URL", self.helius_wss_url.expose(), &["ws", "wss"]);
+        if let Some(u) = &self.quicknode_http_url {
+            check_url(&mut r, "rpc.quicknode_http_url / QUICKNODE_HTTP_URL", u.expose(), &["http", "https"]);
+        }
+        if let Some(u) = &self.quicknode_wss_url {
+            check_url(&mut r, "rpc.quicknode_wss_url / QUICKNODE_WSS_URL", u.expose(), &["ws", "wss"]);
+        }
+        check_url(&mut r, "execution.jupiter_base_url / JUPITER_BASE_URL", &self.jupiter_base_url, &["http", "https"]);
+        if !self.market_data_url.is_empty() {
+            check_url(&mut r, "execution.market_data_url / SIE_MARKET_DATA_URL", &self.market_data_url, &["http", "https"]);
+        }
+        if let Some(u) = &self.slack_webhook_url {
+            check_url(&mut r, "alerts.slack_webhook_url / SLACK_WEBHOOK_URL", u.expose(), &["https"]);
+        }
+        if let Some(u) = &self.discord_webhook_url {
+            check_url(&mut r, "alerts.discord_webhook_url / DISCORD_WEBHOOK_URL", u.expose(), &["https"]);
+        }
+        if let Some(u) = &self.alert_webhook_url {
+            check_url(&mut r, "alerts.webhook_url / SIE_ALERT_WEBHOOK_URL", u.expose(), &["http", "https"]);
+        }
+        if self.alert_suppress_secs == 0 {
+            r.warn("alerts.suppress_secs / SIE_ALERT_SUPPRESS_SECS", "0 disables deduplication; every repeat is sent");
+        }
+        if self.alert_escalate_secs > 0 && self.alert_escalate_secs < self.alert_suppress_secs {
+            r.warn(
+                "alerts.escalate_secs / SIE_ALERT_ESCALATE_SECS",
+                "shorter than suppress_secs; escalation happens at the first digest",
+            );
+        }
+        check_url(
+            &mut r,
+            "alerts.explorer_tx_url / SIE_EXPLORER_TX_URL",
+            &self.explorer_tx_url,
+            &["https"],
+        );
+        if !self.explorer_tx_url.contains("{sig}") {
+            r.warn(
+                "alerts.explorer_tx_url / SIE_EXPLORER_TX_URL",
+                "has no {sig} placeholder; tx links will not point at the transaction",
+            );
+        }
+        if self.alert_max_attempts == 0 {
+            r.error("alerts.max_attempts / SIE_ALERT_MAX_ATTEMPTS", "must be at least 1");
+        }
+        match (&self.telegram_bot_token, &self.telegram_chat_id) {
+            (Some(_), None) => r.error("alerts.telegram_chat_id / TELEGRAM_CHAT_ID", "required with a Telegram bot token"),
+            (None, Some(_)) => r.error("alerts.telegram_bot_token / TELEGRAM_BOT_TOKEN", "required with a Telegram chat id"),
+            _ => {}
+        }
+
+        // Keys / live trading
+        let live = !self.dry_run;
+        if self.signer == "remote" {
+            match &self.signer_url {
+                Some(u) => check_url(&mut r, "keys.signer_url / SIE_SIGNER_URL", u, &["http", "https"]),
+                None => r.error("keys.signer_url / SIE_SIGNER_URL", "required when signer=remote"),
+            }
+            if self.signer_token.is_none() {
+                r.error("keys.signer_token / SIE_SIGNER_TOKEN", "required when signer=remote");
+            }
+        } else if self.signer != "local" {
+            r.error("keys.signer / SIE_SIGNER", format!("'{}' is not local or remote", self.signer));
+        } else if let Some(p) = &self.keystore_path {
+            check_keypair_file(&mut r, "keys.keystore_path / SIE_KEYSTORE_PATH", p.expose(), live);
+            match &self.keystore_passphrase_file {
+                Some(f) => check_keypair_file(
+                    &mut r,
+                    "keys.passphrase_file / SIE_KEYSTORE_PASSPHRASE_FILE",
+                    f.expose(),
+                    live,
+                ),
+                None if !crate::keystore::has_env_passphrase() => {
+                    let msg = "keystore set but no passphrase (passphrase file or SIE_KEYSTORE_PASSPHRASE)";
+                    if live {
+                        r.error("keys.passphrase_file / SIE_KEYSTORE_PASSPHRASE_FILE", msg)
+                    } else {
+                        r.warn("keys.passphrase_file / SIE_KEYSTORE_PASSPHRASE_FILE", msg)
+                    }
+                }
+                None => {}
+            }
+            if self.sol_keypair_path.is_some() {
+                r.warn("keys.sol_keypair_path / SOL_KEYPAIR_PATH", "ignored: keystore_path takes precedence");
+            }
+        } else if let Some(p) = &self.sol_keypair_path {
+            check_keypair_file(&mut r, "keys.sol_keypair_path / SOL_KEYPAIR_PATH", p.expose(), live);
+            if live {
+                r.warn(
+                    "keys.sol_keypair_path / SOL_KEYPAIR_PATH",
+                    "plaintext keypair; prefer an encrypted keystore (solbot keystore import)",
+                );
+            }
+        } else if live {
+            r.error(
+                "keys.keystore_path / SIE_KEYSTORE_PATH",
+                "a keystore (or SOL_KEYPAIR_PATH) is required when dry_run=false",
+            );
+        }
+        if !self.dry_run && !self.has_alert_sink() {
+            r.warn(
+                "alerts",
+                "live trading without any alert sink (Slack, Telegram, Discord or webhook)",
+            );
+        }
+
+        // Control API
+        match self.control_addr.parse::<std::net::SocketAddr>() {
+            Ok(a) if !a.ip().is_loopback() => r.warn(
+                "control.addr / SIE_CONTROL_ADDR",
+                format!("{a} is not loopback; the control API should stay local"),
+            ),
+            Ok(_) => {}
+            Err(e) => r.error("control.addr / SIE_CONTROL_ADDR", format!("not a socket address: {e}")),
+        }
+        if let Some(raw) = &self.control_tokens {
+            if let Err(e) = crate::control::parse_operator_tokens(raw.expose()) {
+                r.error("control.tokens / SIE_CONTROL_TOKENS", e.to_string());
+            }
+        }
+
+        // Metrics / health endpoints
+        if let Err(e) = self.monitoring_addr.parse::<std::net::SocketAddr>() {
+            r.error("monitoring.addr / SIE_MONITORING_ADDR", format!("not a socket address: {e}"));
+        }
+        if let Some(url) = &self.otlp_endpoint {
+            check_url(&mut r, "monitoring.otlp_endpoint / SIE_OTLP_ENDPOINT", url, &["http", "https"]);
+        }
+        match self.health_max_tick_age_secs {
+            0 => r.error("monitoring.max_tick_age_secs / SIE_HEALTH_MAX_TICK_AGE_SECS", "must be > 0"),
+            s if s < 15 => r.warn(
+                "monitoring.max_tick_age_secs / SIE_HEALTH_MAX_TICK_AGE_SECS",
+                format!("{s}s leaves little room over the 5s positions loop; slow quotes will flap health"),
+            ),
+            s if s < 45 => r.warn(
+                "monitoring.max_tick_age_secs / SIE_HEALTH_MAX_TICK_AGE_SECS",
+                format!("{s}s is shorter than one exit swap (up to 30s confirm + 5s market context); liquidations will flap health"),
+            ),
+            _ => {}
+        }
+
+        // Chat commands
+        if self.slack_signing_secret.is_some() {
+            if let Err(e) = self.chatops_addr.parse::<std::net::SocketAddr>() {
+                r.error("chatops.addr / SIE_CHATOPS_ADDR", format!("not a socket address: {e}"));
+            }
+        }
+        if self.chatops_confirm_secs == 0 {
+            r.error("chatops.confirm_secs / SIE_CHATOPS_CONFIRM_SECS", "must be > 0");
+        }
+        for (enabled, key, raw, what) in [
+            (
+                self.slack_signing_secret.is_some(),
+                "chatops.slack_operators / SIE_SLACK_OPERATORS",
+                &self.slack_operators,
+                "Slack commands",
+            ),
+            (
+                self.telegram_commands,
+                "chatops.telegram_operators / SIE_TELEGRAM_OPERATORS",
+                &self.telegram_operators,
+                "Telegram commands",
+            ),
+        ] {
+            match raw.as_deref().map(crate::chatops::parse_operator_ids) {
+                Some(Err(e)) => r.error(key, e.to_string()),
+                Some(Ok(ops)) if ops.is_empty() && enabled => r.error(key, format!("{what} need at least one operator")),
+                None if enabled => r.error(key, format!("{what} need at least one operator (user_id:name)")),
+                _ => {}
+            }
+        }
+        if self.telegram_commands && self.telegram_bot_token.is_none() {
+            r.error(
+                "chatops.telegram_commands / SIE_TELEGRAM_COMMANDS",
+                "requires TELEGRAM_BOT_TOKEN",
+            );
+        }
+
+        // Strategies
+        let m = &self.strategies.momentum;
+        if m.rsi_period < 2 {
+            r.error("strategies.momentum.rsi_period", "must be >= 2");
+        }
+        if !(m.rsi_breakout > 0.0 && m.rsi_breakout < 100.0) {
+            r.error("strategies.momentum.rsi_breakout", "must be in (0, 100)");
+        }
+        for mint in &m.watchlist {
+            if mint.parse::<solana_sdk::pubkey::Pubkey>().is_err() {
+                r.error("strategies.momentum.watchlist", format!("'{mint}' is not a valid mint address"));
+            }
+        }
+
+        r
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secret::Secret;
+
+    fn defaults() -> Config {
+        Config::load_layers().unwrap()
+    }
+
+    fn error_keys(r: &ValidationReport) -> Vec<&str> {
+        r.issues
+            .iter()
+            .filter(|i| i.severity == Severity::Error)
+            .map(|i| i.key.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn defaults_validate_without_errors() {
+        let r = defaults().validate();
+        assert!(!r.has_errors(), "{r}");
+    }
+
+    #[test]
+    fn cross_field_errors_are_all_reported() {
+        let mut cfg = defaults();
+        cfg.capital_usdc = 100.0;
+        cfg.position_size_usdc = 30.0;
+        cfg.max_open_positions = 4;
+        cfg.trailing_arm_pct = cfg.stop_loss_pct;
+        cfg.slippage_bps = 200;
+        cfg.max_slippage_bps = 100;
+        cfg.telegram_bot_token = Some(Secret::new("123:abc"));
+        cfg.telegram_chat_id = None;
+
+        let r = cfg.validate();
+        assert!(r.has_errors());
+        assert_eq!(
+            error_keys(&r),
+            vec![
+                "risk.position_size_usdc / SIE_POSITION_SIZE_USDC",
+                "risk.trailing_arm_pct / SIE_TRAILING_ARM_PCT",
+                "execution.slippage_bps / SIE_SLIPPAGE_BPS",
+                "alerts.telegram_chat_id / TELEGRAM_CHAT_ID",
+            ]
+        );
+        assert!(r.to_string().contains("exceeds capital_usdc = 100.00"), "{r}");
+    }
+
+    #[test]
+    fn live_trading_needs_a_signer() {
+        let mut cfg = defaults();
+        cfg.dry_run = false;
+        assert_eq!(error_keys(&cfg.validate()), vec!["keys.keystore_path / SIE_KEYSTORE_PATH"]);
+
+        cfg.signer = "remote".into();
+        assert_eq!(
+            error_keys(&cfg.validate()),
+            vec!["keys.signer_url / SIE_SIGNER_URL", "keys.signer_token / SIE_SIGNER_TOKEN"]
+        );
+    }
+
+    #[test]
+    fn a_daily_limit_above_the_hard_stop_is_only_a_warning() {
+        let mut cfg = defaults();
+        cfg.max_daily_loss_pct = 0.25;
+        let r = cfg.validate();
+        assert!(!r.has_errors(), "{r}");
+        assert!(r.warnings().any(|i| i.key == "risk.max_daily_loss_pct / SIE_MAX_DAILY_LOSS_PCT"));
+    }
+}